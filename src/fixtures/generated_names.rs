@@ -0,0 +1,2 @@
+// What a build script writing the array out as an item would generate.
+pub const GENERATED_NAMES: [&str; 3] = ["alpha", "beta", "gamma"];
@@ -0,0 +1 @@
+["alpha", "beta", "gamma"]
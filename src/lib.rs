@@ -34,14 +34,40 @@
 
 #[doc(hidden)]
 pub const fn concated_size<const N: usize>(array: [&'static str; N], sep: &'static str) -> usize {
-    let mut n = if N == 0 { 0 } else { N - 1 } * sep.len();
-    let mut i = N;
+    required_size(&array, non_empty_sep(sep))
+}
+
+/// Maps an empty separator to `None`, the representation both
+/// [`required_size`] and the writers expect for "no separator".
+#[doc(hidden)]
+pub const fn non_empty_sep(sep: &str) -> Option<&str> {
+    if sep.is_empty() { None } else { Some(sep) }
+}
+
+/// Returns the number of bytes required to join `inputs` with `sep`.
+///
+/// `None` means no separator at all and yields the same size as an
+/// empty separator.
+///
+/// ```rust
+/// use const_str_join::required_size;
+/// assert_eq!(required_size(&["a", "bc"], Some(", ")), 5);
+/// assert_eq!(required_size(&["a", "bc"], None), 3);
+/// ```
+pub const fn required_size(inputs: &[&str], sep: Option<&str>) -> usize {
+    let mut n = 0;
+    if let Some(sep) = sep
+        && !inputs.is_empty()
+    {
+        n += (inputs.len() - 1) * sep.len();
+    }
+    let mut i = inputs.len();
     loop {
-        if i <= 0 {
+        if i == 0 {
             break;
         }
         i -= 1;
-        n += array[i].len();
+        n += get_str(inputs, i).len();
     }
     n
 }
@@ -52,6 +78,9 @@ pub const fn copy_bytes(src: &[u8], dest: &mut [u8], offset: usize) -> usize {
     let mut op = offset;
     if !src.is_empty() {
         loop {
+            if op >= dest.len() {
+                index_out_of_bounds(op, dest.len());
+            }
             assert!(dest[op] == b'\0');
             dest[op] = src[i];
             op += 1;
@@ -66,18 +95,18 @@ pub const fn copy_bytes(src: &[u8], dest: &mut [u8], offset: usize) -> usize {
 }
 
 #[doc(hidden)]
-pub const fn join_strings(inputs: &[&str], sep: Option<&str>, mut output: &mut [u8]) -> usize {
-    assert!(output.len() > 0);
+pub const fn join_strings(inputs: &[&str], sep: Option<&str>, output: &mut [u8]) -> usize {
+    assert!(!output.is_empty());
     let mut n = 0;
     let mut op = 0;
     loop {
-        op = copy_bytes(&inputs[n].as_bytes(), &mut output, op);
+        op = copy_bytes(get_str(inputs, n).as_bytes(), output, op);
 
         if n + 1 < inputs.len()
             && let Some(sep) = sep
         {
             let s = sep.as_bytes();
-            op = copy_bytes(s, &mut output, op);
+            op = copy_bytes(s, output, op);
         }
 
         n += 1;
@@ -88,53 +117,203 @@ pub const fn join_strings(inputs: &[&str], sep: Option<&str>, mut output: &mut [
     op
 }
 
+/// Wrapper used to accept either a `&str` or a `char` as separator.
+///
+/// There are no const traits (yet), instead each supported type gets
+/// its own inherent impl and the compiler picks the matching one
+/// based on the type of the wrapped value.
+#[doc(hidden)]
+pub struct Separator<T>(pub T);
+
+impl Separator<&str> {
+    pub const fn sep_len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub const fn write_sep(&self, dest: &mut [u8], offset: usize) -> usize {
+        copy_bytes(self.0.as_bytes(), dest, offset)
+    }
+}
+
+impl Separator<char> {
+    pub const fn sep_len(&self) -> usize {
+        self.0.len_utf8()
+    }
+
+    pub const fn write_sep(&self, dest: &mut [u8], offset: usize) -> usize {
+        let mut buffer = [0u8; 4];
+        let encoded = self.0.encode_utf8(&mut buffer);
+        copy_bytes(encoded.as_bytes(), dest, offset)
+    }
+}
+
+/// Turns a separator (`&str` or `char`) into a `&'static str`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __separator {
+    ($sep:expr) => {
+        const {
+            const __SEP_LEN: usize = $crate::Separator($sep).sep_len();
+            // a const rather than a static: separators are tiny and clippy
+            // ICEs when const-evaluating references to statics
+            const __SEP_STORAGE: [u8; __SEP_LEN] = {
+                let mut buffer = [0u8; __SEP_LEN];
+                $crate::Separator($sep).write_sep(&mut buffer, 0);
+                buffer
+            };
+            if let Ok(v) = core::str::from_utf8(&__SEP_STORAGE) {
+                v
+            } else {
+                panic!("separator isn't a valid utf8 string");
+            }
+        }
+    };
+}
+
 /// Returns a buffer (`[u8; N]`) that contains the joined string as bytes.
 ///
+/// The separator can either be a `&str` or a `char`.
 /// Example usage:
 /// ```rust
 /// let s: [u8; _] = const_str_join::joined_array!(["A", "B", "C"], "<>");
 /// assert_eq!(&s, b"A<>B<>C")
 /// ```
+///
+/// Passing a size and a [`SizePolicy`] writes into a buffer of that size
+/// whether or not the joined string fits exactly:
+/// ```rust
+/// use const_str_join::{joined_array, SizePolicy};
+///
+/// let exact: [u8; 5] = joined_array!(["ab", "cd"], ",", 5, SizePolicy::Exact);
+/// assert_eq!(&exact, b"ab,cd");
+/// let padded: [u8; 7] = joined_array!(["ab", "cd"], ",", 7, SizePolicy::Pad);
+/// assert_eq!(&padded, b"ab,cd\0\0");
+/// let truncated: [u8; 4] = joined_array!(["ab", "cd"], ",", 4, SizePolicy::Truncate);
+/// assert_eq!(&truncated, b"ab,c");
+/// ```
 #[macro_export]
 macro_rules! joined_array {
+    (sep = $sep:expr, $array:expr) => {
+        $crate::joined_array!($array, $sep)
+    };
     ($array:expr, $sep:expr) => {
         const {
-            const SIZE: usize = $crate::concated_size($array, $sep);
-	    $crate::joined_array!($array, $sep, SIZE)
+            const __SEP: Option<&str> = $crate::non_empty_sep($crate::__separator!($sep));
+            const SIZE: usize = $crate::required_size(&$array, __SEP);
+            $crate::joined_array!($array, $sep, SIZE)
+        }
+    };
+    ($array:expr, $sep:expr, $size:expr, $policy:expr) => {
+        const {
+            const ARRAY_LEN: usize = $size;
+            const __ARRAY: &[&str] = &$array;
+            const __SEP: Option<&str> = $crate::non_empty_sep($crate::__separator!($sep));
+            const __POLICY: $crate::SizePolicy = $policy;
+            const __WRITTEN: usize =
+                $crate::policy_joined_size(__ARRAY, __SEP, ARRAY_LEN, __POLICY, false);
+
+            let mut buffer = [0u8; ARRAY_LEN];
+            let next_position = $crate::write_policy_joined(__ARRAY, __SEP, &mut buffer);
+
+            // the bytes after `__WRITTEN` are the zero-filled tail of Pad and Truncate
+            assert!(next_position == __WRITTEN);
+            buffer
         }
     };
     ($array:expr, $sep:expr, $size:expr) => {
         const {
             const ARRAY_LEN: usize = $size;
 
-            let sep = $sep;
-	    let sep = if sep.len() > 0 { Some(sep) } else { None };
+            let sep = $crate::non_empty_sep($crate::__separator!($sep));
             let array = &$array;
 
             let mut buffer = [0u8; ARRAY_LEN];
-	    let next_position = $crate::join_strings(array, sep, &mut buffer);
+            let next_position = $crate::join_strings(array, sep, &mut buffer);
 
-	    // when we are done we should have written to all the bytes, if not then something is off
-	    assert!(next_position == ARRAY_LEN);
+            // when we are done we should have written to all the bytes, if not then something is off
+            assert!(next_position == ARRAY_LEN);
             buffer
         }
     };
-
 }
 
 /// Returns a new constant value with the joined string of `array` and `sep`.
+///
+/// Just like [`joined_array!`] the separator can be a `&str` or a `char`.
+///
 /// Example usage:
 /// ```rust
 /// const FLAGS: &'static str = const_str_join::const_join!(["--help", "--version", "--verbose"], "|");
 /// const HELP: &'static str = const_str_join::const_join!(["flags:", FLAGS], " ");
 /// assert_eq!(HELP, "flags: --help|--version|--verbose");
+///
+/// const SEP: char = '/';
+/// const PATH: &'static str = const_str_join::const_join!(["usr", "bin"], SEP);
+/// assert_eq!(PATH, "usr/bin");
+/// ```
+///
+/// The separator may also be given first, which reads better when the
+/// array literal is long:
+/// ```rust
+/// const LIST: &'static str = const_str_join::const_join!(sep = ", ", ["a", "b", "c"]);
+/// assert_eq!(LIST, "a, b, c");
+/// ```
+///
+/// Elements of an array literal can be any `&'static str` expression,
+/// including other macros:
+/// ```rust
+/// const ID: &'static str = const_str_join::const_join!([env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")], "-");
+/// ```
+///
+/// Arrays generated by a build script can be used the same way, either
+/// as a named const from an `include!`d file or by passing
+/// `include!(concat!(env!("OUT_DIR"), "/names.rs"))` for a file that only
+/// contains the array expression.
+///
+/// Like [`joined_array!`] a size and a [`SizePolicy`] may be given. With
+/// [`SizePolicy::Truncate`] the string ends at the last char boundary
+/// that fits, so it can be shorter than the requested size:
+/// ```rust
+/// use const_str_join::{const_join, SizePolicy};
+///
+/// const SHORT: &'static str = const_join!(["größe", "maß"], " ", 4, SizePolicy::Truncate);
+/// assert_eq!(SHORT, "grö");
+/// ```
+///
+/// [`SizePolicy::Pad`] would leave NUL bytes at the end of the string, so
+/// it is only accepted by [`joined_array!`]:
+/// ```compile_fail
+/// use const_str_join::{const_join, SizePolicy};
+///
+/// const PADDED: &'static str = const_join!(["a", "b"], "-", 5, SizePolicy::Pad);
 /// ```
-
 #[macro_export]
 macro_rules! const_join {
+    (sep = $sep:expr, $array:expr) => {
+        $crate::const_join!($array, $sep)
+    };
+    ([$($elem:expr),* $(,)?], $sep:expr) => {
+        const {
+            // bind the elements first so each of them is only evaluated once
+            // and has to be a `&'static str` on its own
+            const __ELEMS: &[&str] = &[$($elem),*];
+            $crate::const_join!(*__ELEMS, $sep)
+        }
+    };
+    ($array:expr, $sep:expr, $size:expr, $policy:expr) => {
+        const {
+            const __ARRAY: &[&str] = &$array;
+            const __SEP: Option<&str> = $crate::non_empty_sep($crate::__separator!($sep));
+            $crate::__build_str!(
+                $crate::policy_joined_size(__ARRAY, __SEP, $size, $policy, true),
+                |buffer| $crate::write_policy_joined(__ARRAY, __SEP, &mut buffer)
+            )
+        }
+    };
     ($array:expr, $sep:expr) => {
         const {
-            const SIZE: usize = $crate::concated_size($array, $sep);
+            const __SEP: Option<&str> = $crate::non_empty_sep($crate::__separator!($sep));
+            const SIZE: usize = $crate::required_size(&$array, __SEP);
             static STORAGE: [u8; SIZE] = $crate::joined_array!($array, $sep, SIZE);
             // no unwrap in const :|
             if let Ok(v) = core::str::from_utf8(&STORAGE) {
@@ -146,27 +325,6582 @@ macro_rules! const_join {
     };
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Builds a `&'static str` of exactly `$size` bytes using `$write`.
+///
+/// `$write` gets a zeroed `[u8; $size]` buffer bound to `$buffer` and
+/// must return the position after the last written byte.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __build_str {
+    ($size:expr, |$buffer:ident| $write:expr) => {
+        const {
+            const __SIZE: usize = $size;
+            static __STORAGE: [u8; __SIZE] = {
+                let mut $buffer = [0u8; __SIZE];
+                let next_position: usize = $write;
+                // when we are done we should have written to all the bytes, if not then something is off
+                assert!(next_position == __SIZE);
+                $buffer
+            };
+            if let Ok(v) = core::str::from_utf8(&__STORAGE) {
+                v
+            } else {
+                panic!("joined array isn't a valid utf8 string");
+            }
+        }
+    };
+}
+
+/// Returns the number of chars (unicode scalar values) in `s`.
+///
+/// ```rust
+/// assert_eq!(const_str_join::char_count("abc"), 3);
+/// assert_eq!(const_str_join::char_count("äöü"), 3);
+/// ```
+pub const fn char_count(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let mut n = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        // every byte that isn't a continuation byte starts a new char
+        if bytes[i] & 0b1100_0000 != 0b1000_0000 {
+            n += 1;
+        }
+        i += 1;
+    }
+    n
+}
 
-    const A: &'static str = "A";
-    const B: &'static str = "B";
-    const C: &'static str = "C";
-    const ARRAY_OF_STRINGS: [&'static str; 3] = [A, B, C];
+#[doc(hidden)]
+pub const fn max_char_count(inputs: &[&str]) -> usize {
+    let mut max = 0;
+    let mut i = 0;
+    while i < inputs.len() {
+        let n = char_count(get_str(inputs, i));
+        if n > max {
+            max = n;
+        }
+        i += 1;
+    }
+    max
+}
 
-    #[test]
-    fn nested() {
-        const FOO: &'static str = const_join!(ARRAY_OF_STRINGS, ":");
-        const MORE_PARTS: [&'static str; 3] = ["<", FOO, ">"];
-        const MORE: &'static str = const_join!(MORE_PARTS, "");
-        assert_eq!(FOO, "A:B:C");
-        assert_eq!(MORE, "<A:B:C>");
+/// Writes `src` followed by as many `fill` bytes as required to reach
+/// `width` chars. Strings that are already wider are written as-is.
+#[doc(hidden)]
+pub const fn pad_right_str(
+    src: &str,
+    width: usize,
+    fill: u8,
+    dest: &mut [u8],
+    offset: usize,
+) -> usize {
+    let mut op = copy_bytes(src.as_bytes(), dest, offset);
+    let mut n = char_count(src);
+    while n < width {
+        dest[op] = fill;
+        op += 1;
+        n += 1;
     }
+    op
+}
 
-    #[test]
-    fn joined_array() {
-        let s = joined_array!(ARRAY_OF_STRINGS, "-");
-        assert_eq!(&s, b"A-B-C");
+#[doc(hidden)]
+pub const fn columns_size(inputs: &[&str], sep: &str) -> usize {
+    let width = max_char_count(inputs);
+    let mut n = if inputs.is_empty() {
+        0
+    } else {
+        inputs.len() - 1
+    } * sep.len();
+    let mut i = 0;
+    while i < inputs.len() {
+        n += get_str(inputs, i).len() + (width - char_count(get_str(inputs, i)));
+        i += 1;
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_columns(inputs: &[&str], sep: &str, output: &mut [u8]) -> usize {
+    let width = max_char_count(inputs);
+    let mut op = 0;
+    let mut i = 0;
+    while i < inputs.len() {
+        if i > 0 {
+            op = copy_bytes(sep.as_bytes(), output, op);
+        }
+        op = pad_right_str(get_str(inputs, i), width, b' ', output, op);
+        i += 1;
+    }
+    op
+}
+
+/// Pads every element of `array` with spaces to the width (in chars) of
+/// the widest element and joins the result with `sep`.
+///
+/// Every element is padded, including the last one, so the result
+/// usually ends with trailing spaces.
+///
+/// Example usage:
+/// ```rust
+/// const COLUMNS: &'static str = const_str_join::declare_columns_str!(["a", "bbb", "cc"], " ");
+/// assert_eq!(COLUMNS, "a   bbb cc ");
+/// ```
+#[macro_export]
+macro_rules! declare_columns_str {
+    ($array:expr, $sep:expr) => {
+        const {
+            const __SEP: &str = $crate::__separator!($sep);
+            $crate::__build_str!($crate::columns_size(&$array, __SEP), |buffer| {
+                $crate::write_columns(&$array, __SEP, &mut buffer)
+            })
+        }
+    };
+}
+
+/// Returns whether every byte of `s` is ASCII (`< 0x80`).
+///
+/// Useful to guard transformations that only work bytewise.
+///
+/// ```rust
+/// const _: () = assert!(const_str_join::is_ascii("abc"));
+/// const _: () = assert!(!const_str_join::is_ascii("äbc"));
+/// ```
+pub const fn is_ascii(s: &str) -> bool {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] >= 0x80 {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+#[doc(hidden)]
+pub const fn each_affixed_size(inputs: &[&str], sep: &str, prefix: &str, suffix: &str) -> usize {
+    let mut n = if inputs.is_empty() {
+        0
+    } else {
+        inputs.len() - 1
+    } * sep.len();
+    let mut i = 0;
+    while i < inputs.len() {
+        n += prefix.len() + get_str(inputs, i).len() + suffix.len();
+        i += 1;
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_each_affixed(
+    inputs: &[&str],
+    sep: &str,
+    prefix: &str,
+    suffix: &str,
+    output: &mut [u8],
+) -> usize {
+    let mut op = 0;
+    let mut i = 0;
+    while i < inputs.len() {
+        if i > 0 {
+            op = copy_bytes(sep.as_bytes(), output, op);
+        }
+        op = copy_bytes(prefix.as_bytes(), output, op);
+        op = copy_bytes(get_str(inputs, i).as_bytes(), output, op);
+        op = copy_bytes(suffix.as_bytes(), output, op);
+        i += 1;
+    }
+    op
+}
+
+/// Joins `array` with `sep` while prefixing every element with `prefix`.
+///
+/// Example usage:
+/// ```rust
+/// const ARGS: &'static str = const_str_join::declare_joined_prefix_each_str!(["a.txt", "b.txt"], " ", "-f ");
+/// assert_eq!(ARGS, "-f a.txt -f b.txt");
+/// ```
+#[macro_export]
+macro_rules! declare_joined_prefix_each_str {
+    ($array:expr, $sep:expr, $prefix:expr) => {
+        $crate::declare_each_affixed_join_str!($array, $sep, $prefix, "")
+    };
+}
+
+/// Joins `array` with `sep` while wrapping every element in `prefix` and
+/// `suffix`. Either of them may be empty.
+///
+/// Example usage:
+/// ```rust
+/// use const_str_join::declare_each_affixed_join_str;
+///
+/// const QUOTED: &'static str = declare_each_affixed_join_str!(["a", "b"], ", ", "'", "'");
+/// assert_eq!(QUOTED, "'a', 'b'");
+/// const FILES: &'static str = declare_each_affixed_join_str!(["main", "lib"], " ", "", ".rs");
+/// assert_eq!(FILES, "main.rs lib.rs");
+/// ```
+#[macro_export]
+macro_rules! declare_each_affixed_join_str {
+    ($array:expr, $sep:expr, $prefix:expr, $suffix:expr) => {
+        const {
+            const __SEP: &str = $crate::__separator!($sep);
+            const __PREFIX: &str = $prefix;
+            const __SUFFIX: &str = $suffix;
+            $crate::__build_str!(
+                $crate::each_affixed_size(&$array, __SEP, __PREFIX, __SUFFIX),
+                |buffer| {
+                    $crate::write_each_affixed(&$array, __SEP, __PREFIX, __SUFFIX, &mut buffer)
+                }
+            )
+        }
+    };
+}
+
+#[doc(hidden)]
+pub const fn pairs_size(pairs: &[(&str, &str)], kv_sep: &str, sep: &str) -> usize {
+    let mut n = if pairs.is_empty() { 0 } else { pairs.len() - 1 } * sep.len();
+    let mut i = 0;
+    while i < pairs.len() {
+        n += get_elem(pairs, i).0.len() + kv_sep.len() + get_elem(pairs, i).1.len();
+        i += 1;
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_pairs(
+    pairs: &[(&str, &str)],
+    kv_sep: &str,
+    sep: &str,
+    output: &mut [u8],
+) -> usize {
+    let mut op = 0;
+    let mut i = 0;
+    while i < pairs.len() {
+        if i > 0 {
+            op = copy_bytes(sep.as_bytes(), output, op);
+        }
+        op = copy_bytes(get_elem(pairs, i).0.as_bytes(), output, op);
+        op = copy_bytes(kv_sep.as_bytes(), output, op);
+        op = copy_bytes(get_elem(pairs, i).1.as_bytes(), output, op);
+        i += 1;
+    }
+    op
+}
+
+/// Joins the entries of a `PATH`-like environment variable with `sep`.
+///
+/// This is the same as [`const_join!`] and only exists for readability.
+///
+/// Example usage:
+/// ```rust
+/// const PATH: &'static str = const_str_join::declare_env_str!(["/usr/bin", "/bin"], ":");
+/// assert_eq!(PATH, "/usr/bin:/bin");
+/// ```
+#[macro_export]
+macro_rules! declare_env_str {
+    ($array:expr, $sep:expr) => {
+        $crate::const_join!($array, $sep)
+    };
+}
+
+/// Renders `(key, value)` pairs as dotenv-style `KEY=value` lines.
+///
+/// Lines are separated by `\n`, there is no trailing newline.
+///
+/// Example usage:
+/// ```rust
+/// const ENV: &'static str = const_str_join::declare_env_pairs_str!([("KEY", "val"), ("FOO", "bar")]);
+/// assert_eq!(ENV, "KEY=val\nFOO=bar");
+/// ```
+#[macro_export]
+macro_rules! declare_env_pairs_str {
+    ($pairs:expr) => {
+        $crate::__build_str!($crate::pairs_size(&$pairs, "=", "\n"), |buffer| {
+            $crate::write_pairs(&$pairs, "=", "\n", &mut buffer)
+        })
+    };
+}
+
+/// Returns the length of the CSI escape sequence (`\x1b[` ... final byte
+/// in `@`..=`~`) starting at `bytes[i]`, or `0` if there is none. An
+/// unterminated sequence extends to the end of `bytes`.
+#[doc(hidden)]
+pub const fn csi_len(bytes: &[u8], i: usize) -> usize {
+    if i + 1 >= bytes.len() || bytes[i] != 0x1b || bytes[i + 1] != b'[' {
+        return 0;
+    }
+    let mut j = i + 2;
+    while j < bytes.len() {
+        if bytes[j] >= b'@' && bytes[j] <= b'~' {
+            return j + 1 - i;
+        }
+        j += 1;
+    }
+    j - i
+}
+
+#[doc(hidden)]
+pub const fn strip_ansi_size(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let mut n = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let skip = csi_len(bytes, i);
+        if skip > 0 {
+            i += skip;
+        } else {
+            n += 1;
+            i += 1;
+        }
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_strip_ansi(s: &str, output: &mut [u8]) -> usize {
+    let bytes = s.as_bytes();
+    let mut op = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let skip = csi_len(bytes, i);
+        if skip > 0 {
+            i += skip;
+        } else {
+            output[op] = bytes[i];
+            op += 1;
+            i += 1;
+        }
+    }
+    op
+}
+
+/// Removes all ANSI CSI escape sequences (e.g. colors) from `s`.
+///
+/// A sequence starts with `\x1b[` and ends with the first byte in the
+/// range `@`..=`~`. Other escape sequences are kept as-is.
+///
+/// Example usage:
+/// ```rust
+/// const COLORED: &'static str = "\x1b[1;31merror\x1b[0m: oops";
+/// const PLAIN: &'static str = const_str_join::strip_ansi_str!(COLORED);
+/// assert_eq!(PLAIN, "error: oops");
+/// ```
+#[macro_export]
+macro_rules! strip_ansi_str {
+    ($s:expr) => {
+        const {
+            const __INPUT: &str = $s;
+            $crate::__build_str!($crate::strip_ansi_size(__INPUT), |buffer| {
+                $crate::write_strip_ansi(__INPUT, &mut buffer)
+            })
+        }
+    };
+}
+
+#[doc(hidden)]
+pub const fn write_repeated(s: &str, times: usize, output: &mut [u8], offset: usize) -> usize {
+    let mut op = offset;
+    let mut i = 0;
+    while i < times {
+        op = copy_bytes(s.as_bytes(), output, op);
+        i += 1;
+    }
+    op
+}
+
+/// Returns `s` repeated `times` times.
+///
+/// Example usage:
+/// ```rust
+/// const RULE: &'static str = const_str_join::repeat_str!("-=", 3);
+/// assert_eq!(RULE, "-=-=-=");
+/// ```
+#[macro_export]
+macro_rules! repeat_str {
+    ($s:expr, $times:expr) => {
+        const {
+            const __INPUT: &str = $s;
+            const __TIMES: usize = $times;
+            $crate::__build_str!(__INPUT.len() * __TIMES, |buffer| {
+                $crate::write_repeated(__INPUT, __TIMES, &mut buffer, 0)
+            })
+        }
+    };
+}
+
+/// Joins `array` with newlines (`\n`), without a trailing newline.
+///
+/// Example usage:
+/// ```rust
+/// const TEXT: &'static str = const_str_join::declare_lines!(["first", "second"]);
+/// assert_eq!(TEXT, "first\nsecond");
+/// ```
+#[macro_export]
+macro_rules! declare_lines {
+    ($array:expr) => {
+        $crate::const_join!($array, "\n")
+    };
+}
+
+/// Fences `body` between two rules made of `delim` repeated `times`
+/// times, each on its own line.
+///
+/// Example usage:
+/// ```rust
+/// const FENCED: &'static str = const_str_join::repeat_join_around!("body", "=", 4);
+/// assert_eq!(FENCED, "====\nbody\n====");
+/// ```
+#[macro_export]
+macro_rules! repeat_join_around {
+    ($body:expr, $delim:expr, $times:expr) => {
+        const {
+            const __RULE: &str = $crate::repeat_str!($delim, $times);
+            const __BODY: &str = $body;
+            $crate::declare_lines!([__RULE, __BODY, __RULE])
+        }
+    };
+}
+
+const fn is_boundary(bytes: &[u8], i: usize) -> bool {
+    i == 0 || i >= bytes.len() || bytes[i] & 0b1100_0000 != 0b1000_0000
+}
+
+/// Returns the first char boundary in `s` at or after `from`.
+///
+/// Positions past the end of `s` are clamped to `s.len()`.
+///
+/// ```rust
+/// use const_str_join::next_char_boundary;
+/// assert_eq!(next_char_boundary("aé", 2), 3);
+/// assert_eq!(next_char_boundary("aé", 1), 1);
+/// ```
+pub const fn next_char_boundary(s: &str, from: usize) -> usize {
+    let bytes = s.as_bytes();
+    if from >= bytes.len() {
+        return bytes.len();
+    }
+    let mut i = from;
+    while !is_boundary(bytes, i) {
+        i += 1;
+    }
+    i
+}
+
+/// Returns the last char boundary in `s` at or before `from`.
+///
+/// Positions past the end of `s` are clamped to `s.len()`.
+///
+/// ```rust
+/// use const_str_join::prev_char_boundary;
+/// assert_eq!(prev_char_boundary("aé", 2), 1);
+/// assert_eq!(prev_char_boundary("aé", 3), 3);
+/// ```
+pub const fn prev_char_boundary(s: &str, from: usize) -> usize {
+    let bytes = s.as_bytes();
+    if from >= bytes.len() {
+        return bytes.len();
+    }
+    let mut i = from;
+    while !is_boundary(bytes, i) {
+        i -= 1;
+    }
+    i
+}
+
+/// Like [`declare_joined_prefix_each_str!`] but uppercases (ASCII only)
+/// the whole result, prefix included.
+///
+/// Example usage:
+/// ```rust
+/// const NAME: &'static str = const_str_join::declare_joined_map_prefix_str!(["a", "b"], "_", "PKG_");
+/// assert_eq!(NAME, "PKG_A_PKG_B");
+/// ```
+#[macro_export]
+macro_rules! declare_joined_map_prefix_str {
+    ($array:expr, $sep:expr, $prefix:expr) => {
+        const {
+            const __SEP: &str = $crate::__separator!($sep);
+            const __PREFIX: &str = $prefix;
+            $crate::__build_str!(
+                $crate::each_affixed_size(&$array, __SEP, __PREFIX, ""),
+                |buffer| {
+                    let next_position =
+                        $crate::write_each_affixed(&$array, __SEP, __PREFIX, "", &mut buffer);
+                    // uppercasing ASCII doesn't change the length
+                    buffer.make_ascii_uppercase();
+                    next_position
+                }
+            )
+        }
+    };
+}
+
+#[doc(hidden)]
+pub const fn bytes_match_at(haystack: &[u8], i: usize, needle: &[u8]) -> bool {
+    if i + needle.len() > haystack.len() {
+        return false;
+    }
+    let mut j = 0;
+    while j < needle.len() {
+        if haystack[i + j] != needle[j] {
+            return false;
+        }
+        j += 1;
+    }
+    true
+}
+
+/// Counts the non-overlapping occurrences of `needle` in `haystack`.
+#[doc(hidden)]
+pub const fn count_bytes(haystack: &[u8], needle: &[u8]) -> usize {
+    assert!(
+        !needle.is_empty(),
+        "the pattern to search for must not be empty"
+    );
+    let mut n = 0;
+    let mut i = 0;
+    while i < haystack.len() {
+        if bytes_match_at(haystack, i, needle) {
+            n += 1;
+            i += needle.len();
+        } else {
+            i += 1;
+        }
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn replaced_bytes_size(input: &[u8], from: &[u8], to: &[u8]) -> usize {
+    let n = count_bytes(input, from);
+    input.len() - n * from.len() + n * to.len()
+}
+
+#[doc(hidden)]
+pub const fn write_replaced_bytes(
+    input: &[u8],
+    from: &[u8],
+    to: &[u8],
+    output: &mut [u8],
+) -> usize {
+    let mut op = 0;
+    let mut i = 0;
+    while i < input.len() {
+        if bytes_match_at(input, i, from) {
+            op = copy_bytes(to, output, op);
+            i += from.len();
+        } else {
+            output[op] = input[i];
+            op += 1;
+            i += 1;
+        }
+    }
+    op
+}
+
+/// Returns a buffer (`[u8; N]`) of `input` with all non-overlapping
+/// occurrences of `from` replaced by `to`.
+///
+/// All arguments are byte slices, they don't have to be valid UTF-8.
+/// An empty `from` fails to compile.
+///
+/// Example usage:
+/// ```rust
+/// let s: [u8; _] = const_str_join::replace_bytes!(b"a\x00b\x00c", b"\x00", b"--");
+/// assert_eq!(&s, b"a--b--c");
+/// ```
+///
+/// ```rust,compile_fail
+/// let s: [u8; _] = const_str_join::replace_bytes!(b"abc", b"", b"--");
+/// ```
+#[macro_export]
+macro_rules! replace_bytes {
+    ($input:expr, $from:expr, $to:expr) => {
+        const {
+            const __INPUT: &[u8] = $input;
+            const __FROM: &[u8] = $from;
+            const __TO: &[u8] = $to;
+            const __SIZE: usize = $crate::replaced_bytes_size(__INPUT, __FROM, __TO);
+
+            let mut buffer = [0u8; __SIZE];
+            let next_position = $crate::write_replaced_bytes(__INPUT, __FROM, __TO, &mut buffer);
+            assert!(next_position == __SIZE);
+            buffer
+        }
+    };
+}
+
+/// Walks `s` collapsing runs of `sep` into a single occurrence, writes
+/// to `output` if given and returns the resulting length.
+const fn collapse_runs(s: &str, sep: &str, mut output: Option<&mut [u8]>) -> usize {
+    let bytes = s.as_bytes();
+    let sep = sep.as_bytes();
+    let mut op = 0;
+    let mut last_was_sep = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        if !sep.is_empty() && bytes_match_at(bytes, i, sep) {
+            if !last_was_sep {
+                if let Some(output) = &mut output {
+                    copy_bytes(sep, output, op);
+                }
+                op += sep.len();
+            }
+            last_was_sep = true;
+            i += sep.len();
+        } else {
+            if let Some(output) = &mut output {
+                output[op] = bytes[i];
+            }
+            op += 1;
+            last_was_sep = false;
+            i += 1;
+        }
+    }
+    op
+}
+
+#[doc(hidden)]
+pub const fn collapsed_size(s: &str, sep: &str) -> usize {
+    collapse_runs(s, sep, None)
+}
+
+#[doc(hidden)]
+pub const fn write_collapsed(s: &str, sep: &str, output: &mut [u8]) -> usize {
+    collapse_runs(s, sep, Some(output))
+}
+
+/// Joins `array` with `sep` and collapses consecutive separators in the
+/// result into a single one.
+///
+/// Runs are collapsed anywhere in the result, including the ones that
+/// were already part of an element and the ones at the very start or end.
+///
+/// Example usage:
+/// ```rust
+/// const S: &'static str = const_str_join::declare_joined_collapse_sep_str!(["a,", "", "b"], ",");
+/// assert_eq!(S, "a,b");
+/// ```
+#[macro_export]
+macro_rules! declare_joined_collapse_sep_str {
+    ($array:expr, $sep:expr) => {
+        const {
+            const __SEP: &str = $crate::__separator!($sep);
+            const __JOINED: &str = $crate::const_join!($array, $sep);
+            $crate::__build_str!($crate::collapsed_size(__JOINED, __SEP), |buffer| {
+                $crate::write_collapsed(__JOINED, __SEP, &mut buffer)
+            })
+        }
+    };
+}
+
+/// Returns the joined bytes of `array` and `sep` as `&'static [u8]`.
+///
+/// Unlike [`const_join!`] this skips the UTF-8 validation.
+///
+/// Example usage:
+/// ```rust
+/// const BYTES: &'static [u8] = const_str_join::declare_joined_bytes!(["GET", "/", "HTTP/1.1"], " ");
+/// assert_eq!(BYTES, b"GET / HTTP/1.1");
+/// ```
+#[macro_export]
+macro_rules! declare_joined_bytes {
+    ($array:expr, $sep:expr) => {
+        const {
+            const __SEP: Option<&str> = $crate::non_empty_sep($crate::__separator!($sep));
+            const __SIZE: usize = $crate::required_size(&$array, __SEP);
+            static __STORAGE: [u8; __SIZE] = $crate::joined_array!($array, $sep, __SIZE);
+            let bytes: &'static [u8] = &__STORAGE;
+            bytes
+        }
+    };
+}
+
+const fn csv_field_needs_quotes(field: &str) -> bool {
+    let bytes = field.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if matches!(bytes[i], b',' | b'"' | b'\n' | b'\r') {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+const fn csv_field_size(field: &str) -> usize {
+    if csv_field_needs_quotes(field) {
+        field.len() + 2 + count_bytes(field.as_bytes(), b"\"")
+    } else {
+        field.len()
+    }
+}
+
+#[doc(hidden)]
+pub const fn csv_row_size(fields: &[&str]) -> usize {
+    let mut n = if fields.is_empty() {
+        0
+    } else {
+        fields.len() - 1
+    };
+    let mut i = 0;
+    while i < fields.len() {
+        n += csv_field_size(get_str(fields, i));
+        i += 1;
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_csv_row(fields: &[&str], output: &mut [u8]) -> usize {
+    let mut op = 0;
+    let mut i = 0;
+    while i < fields.len() {
+        if i > 0 {
+            output[op] = b',';
+            op += 1;
+        }
+        let field = get_str(fields, i).as_bytes();
+        if csv_field_needs_quotes(get_str(fields, i)) {
+            output[op] = b'"';
+            op += 1;
+            let mut j = 0;
+            while j < field.len() {
+                if field[j] == b'"' {
+                    output[op] = b'"';
+                    op += 1;
+                }
+                output[op] = field[j];
+                op += 1;
+                j += 1;
+            }
+            output[op] = b'"';
+            op += 1;
+        } else {
+            op = copy_bytes(field, output, op);
+        }
+        i += 1;
+    }
+    op
+}
+
+/// Builds a single CSV row (RFC 4180) from `fields`.
+///
+/// Fields containing a comma, a double quote or a line break (`\n` or
+/// `\r`) are wrapped in double quotes, double quotes inside of them are
+/// doubled. No line terminator is appended.
+///
+/// Example usage:
+/// ```rust
+/// const ROW: &'static str = const_str_join::declare_csv_row_str!(["a", "b,c", "d\"e"]);
+/// assert_eq!(ROW, "a,\"b,c\",\"d\"\"e\"");
+/// ```
+#[macro_export]
+macro_rules! declare_csv_row_str {
+    ($fields:expr) => {
+        $crate::__build_str!($crate::csv_row_size(&$fields), |buffer| {
+            $crate::write_csv_row(&$fields, &mut buffer)
+        })
+    };
+}
+
+/// Returns the index of the first byte that isn't part of a valid UTF-8
+/// sequence, or `None` if all of `bytes` is valid UTF-8.
+///
+/// A truncated sequence at the end is reported at the index it starts.
+///
+/// ```rust
+/// use const_str_join::first_invalid_index;
+/// assert_eq!(first_invalid_index(b"abc"), None);
+/// assert_eq!(first_invalid_index(b"ab\xffc"), Some(2));
+/// ```
+pub const fn first_invalid_index(bytes: &[u8]) -> Option<usize> {
+    match core::str::from_utf8(bytes) {
+        Ok(_) => None,
+        Err(e) => Some(e.valid_up_to()),
+    }
+}
+
+/// Returns whether `bytes` is valid UTF-8.
+///
+/// ```rust
+/// const _: () = assert!(const_str_join::is_valid_utf8("äöü".as_bytes()));
+/// const _: () = assert!(!const_str_join::is_valid_utf8(b"\xc3"));
+/// ```
+pub const fn is_valid_utf8(bytes: &[u8]) -> bool {
+    first_invalid_index(bytes).is_none()
+}
+
+/// Returns the number of decimal digits required to print `n`.
+#[doc(hidden)]
+pub const fn decimal_len(mut n: u64) -> usize {
+    let mut len = 1;
+    while n >= 10 {
+        n /= 10;
+        len += 1;
+    }
+    len
+}
+
+/// Writes the decimal representation of `n` at `offset`.
+#[doc(hidden)]
+pub const fn write_decimal(mut n: u64, dest: &mut [u8], offset: usize) -> usize {
+    let end = offset + decimal_len(n);
+    let mut op = end;
+    loop {
+        op -= 1;
+        dest[op] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+    end
+}
+
+/// Formats a `usize` as decimal `&'static str`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __usize_str {
+    ($n:expr) => {
+        const {
+            const __N: usize = $n;
+            $crate::__build_str!($crate::decimal_len(__N as u64), |buffer| {
+                $crate::write_decimal(__N as u64, &mut buffer, 0)
+            })
+        }
+    };
+}
+
+/// Fails compilation with `$msg` followed by the index if `$index`
+/// (an `Option<usize>`) is `Some`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __assert_none_at {
+    ($index:expr, $msg:literal) => {
+        const {
+            const __INDEX: Option<usize> = $index;
+            if __INDEX.is_some() {
+                const __AT: usize = if let Some(i) = __INDEX { i } else { 0 };
+                panic!(
+                    "{}",
+                    $crate::const_join!([$msg, $crate::__usize_str!(__AT)], "")
+                );
+            }
+        }
+    };
+}
+
+/// Returns the index of the first byte that makes `s` an invalid
+/// identifier (`[A-Za-z_][A-Za-z0-9_]*`), or `None` if it is valid.
+///
+/// An empty string is reported at index `0`.
+///
+/// ```rust
+/// use const_str_join::first_invalid_ident_index;
+/// assert_eq!(first_invalid_ident_index("my_field2"), None);
+/// assert_eq!(first_invalid_ident_index("1x"), Some(0));
+/// assert_eq!(first_invalid_ident_index("a-b"), Some(1));
+/// ```
+pub const fn first_invalid_ident_index(s: &str) -> Option<usize> {
+    let bytes = s.as_bytes();
+    if bytes.is_empty() {
+        return Some(0);
+    }
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        let valid = b.is_ascii_alphabetic() || b == b'_' || (i > 0 && b.is_ascii_digit());
+        if !valid {
+            return Some(i);
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Joins `array` with `sep` and fails compilation if the result isn't
+/// a valid identifier (`[A-Za-z_][A-Za-z0-9_]*`).
+///
+/// The error message contains the index of the offending byte.
+///
+/// Example usage:
+/// ```rust
+/// const FIELD: &'static str = const_str_join::declare_ident_str!(["my", "field"], "_");
+/// assert_eq!(FIELD, "my_field");
+/// ```
+///
+/// ```rust,compile_fail
+/// const FIELD: &'static str = const_str_join::declare_ident_str!(["1x"], "");
+/// ```
+#[macro_export]
+macro_rules! declare_ident_str {
+    ($array:expr, $sep:expr) => {
+        const {
+            const __JOINED: &str = $crate::const_join!($array, $sep);
+            $crate::__assert_none_at!(
+                $crate::first_invalid_ident_index(__JOINED),
+                "joined string isn't a valid identifier, invalid byte at index "
+            );
+            __JOINED
+        }
+    };
+}
+
+/// Returns `&s[start..end]`, both have to be char boundaries.
+#[doc(hidden)]
+pub const fn str_slice(s: &str, start: usize, end: usize) -> &str {
+    let (head, _) = s.as_bytes().split_at(end);
+    let (_, sliced) = head.split_at(start);
+    if let Ok(v) = core::str::from_utf8(sliced) {
+        v
+    } else {
+        panic!("slice doesn't start and end on char boundaries");
+    }
+}
+
+/// Returns the byte length of the char starting at `bytes[i]`.
+const fn char_len_at(bytes: &[u8], i: usize) -> usize {
+    let b = bytes[i];
+    if b < 0x80 {
+        1
+    } else if b >> 5 == 0b110 {
+        2
+    } else if b >> 4 == 0b1110 {
+        3
+    } else {
+        4
+    }
+}
+
+/// Returns whether the char `bytes[i..i + len]` is one of the chars of `set`.
+const fn set_contains_char(set: &str, bytes: &[u8], i: usize, len: usize) -> bool {
+    let set = set.as_bytes();
+    let mut j = 0;
+    while j < set.len() {
+        let set_len = char_len_at(set, j);
+        if set_len == len {
+            let mut k = 0;
+            while k < len && set[j + k] == bytes[i + k] {
+                k += 1;
+            }
+            if k == len {
+                return true;
+            }
+        }
+        j += set_len;
+    }
+    false
+}
+
+/// Returns the `(start, end)` byte offsets of `s` with all leading and
+/// trailing chars contained in `set` removed.
+#[doc(hidden)]
+pub const fn trim_matches_bounds(s: &str, set: &str) -> (usize, usize) {
+    let bytes = s.as_bytes();
+    let mut start = 0;
+    while start < bytes.len() {
+        let len = char_len_at(bytes, start);
+        if !set_contains_char(set, bytes, start, len) {
+            break;
+        }
+        start += len;
+    }
+    let mut end = bytes.len();
+    while end > start {
+        let char_start = prev_char_boundary(s, end - 1);
+        if !set_contains_char(set, bytes, char_start, end - char_start) {
+            break;
+        }
+        end = char_start;
+    }
+    (start, end)
+}
+
+/// Removes all leading and trailing chars of `s` that are contained in
+/// `set`. Every char of `set` is matched on its own, multi-byte chars
+/// are matched as a whole.
+///
+/// Example usage:
+/// ```rust
+/// const S: &'static str = const_str_join::trim_matches_str!("***hi***", "*");
+/// assert_eq!(S, "hi");
+/// const T: &'static str = const_str_join::trim_matches_str!("-→a-b→-", "-→");
+/// assert_eq!(T, "a-b");
+/// ```
+#[macro_export]
+macro_rules! trim_matches_str {
+    ($s:expr, $set:expr) => {
+        const {
+            const __INPUT: &str = $s;
+            const __BOUNDS: (usize, usize) = $crate::trim_matches_bounds(__INPUT, $set);
+            $crate::str_slice(__INPUT, __BOUNDS.0, __BOUNDS.1)
+        }
+    };
+}
+
+#[doc(hidden)]
+pub const fn write_filled(pattern: &str, width: usize, output: &mut [u8]) -> usize {
+    assert!(is_ascii(pattern), "fill pattern must be ASCII");
+    assert!(
+        !pattern.is_empty() || width == 0,
+        "fill pattern must not be empty"
+    );
+    let pattern = pattern.as_bytes();
+    let mut op = 0;
+    while op < width {
+        output[op] = pattern[op % pattern.len()];
+        op += 1;
+    }
+    op
+}
+
+/// Repeats `pattern` until the result is exactly `width` bytes long,
+/// cutting off the last repetition if required.
+///
+/// Unlike [`repeat_str!`] the pattern doesn't have to fit a whole
+/// number of times. The pattern has to be ASCII so that cutting it
+/// never splits a char.
+///
+/// Example usage:
+/// ```rust
+/// const RULER: &'static str = const_str_join::fill_to_width_str!("-=", 7);
+/// assert_eq!(RULER, "-=-=-=-");
+/// ```
+///
+/// ```rust,compile_fail
+/// const RULER: &'static str = const_str_join::fill_to_width_str!("→", 7);
+/// ```
+#[macro_export]
+macro_rules! fill_to_width_str {
+    ($pattern:expr, $width:expr) => {
+        const {
+            const __WIDTH: usize = $width;
+            $crate::__build_str!(__WIDTH, |buffer| {
+                $crate::write_filled($pattern, __WIDTH, &mut buffer)
+            })
+        }
+    };
+}
+
+/// Like [`join_strings`] but starts writing at `offset` and accepts
+/// empty `inputs`.
+#[doc(hidden)]
+pub const fn write_joined(
+    inputs: &[&str],
+    sep: Option<&str>,
+    output: &mut [u8],
+    offset: usize,
+) -> usize {
+    let mut op = offset;
+    let mut i = 0;
+    while i < inputs.len() {
+        if i > 0
+            && let Some(sep) = sep
+        {
+            op = copy_bytes(sep.as_bytes(), output, op);
+        }
+        op = copy_bytes(get_str(inputs, i).as_bytes(), output, op);
+        i += 1;
+    }
+    op
+}
+
+/// Size and writer of a single [`build_str!`] piece.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __build_piece {
+    (size join($array:expr, $sep:expr)) => {
+        $crate::required_size(&$array, $crate::non_empty_sep($crate::__separator!($sep)))
+    };
+    (size repeat($s:expr, $times:expr)) => {
+        ($s).len() * $times
+    };
+    (size $s:expr) => {
+        ($s).len()
+    };
+    (write $buffer:ident $op:ident join($array:expr, $sep:expr)) => {
+        $crate::write_joined(
+            &$array,
+            $crate::non_empty_sep($crate::__separator!($sep)),
+            &mut $buffer,
+            $op,
+        )
+    };
+    (write $buffer:ident $op:ident repeat($s:expr, $times:expr)) => {
+        $crate::write_repeated($s, $times, &mut $buffer, $op)
+    };
+    (write $buffer:ident $op:ident $s:expr) => {
+        $crate::copy_bytes(($s).as_bytes(), &mut $buffer, $op)
+    };
+}
+
+/// Concatenates a list of pieces into a new `&'static str`.
+///
+/// Every piece is one of:
+///   * `join(ARRAY, SEP)`: `ARRAY` joined with `SEP`, like [`const_join!`],
+///   * `repeat(STR, TIMES)`: `STR` repeated `TIMES` times, like [`repeat_str!`],
+///   * any other expression evaluating to a `&str`.
+///
+/// All pieces are written into a single buffer, there are no
+/// intermediate statics for the nested joins.
+///
+/// Example usage:
+/// ```rust
+/// const ITEMS: [&'static str; 3] = ["a", "b", "c"];
+/// const LIST: &'static str = const_str_join::build_str!("[", join(ITEMS, ", "), "]");
+/// assert_eq!(LIST, "[a, b, c]");
+///
+/// const TITLE: &'static str = const_str_join::build_str!("Title\n", repeat("=", 5));
+/// assert_eq!(TITLE, "Title\n=====");
+/// ```
+#[macro_export]
+macro_rules! build_str {
+    (@munch [$($pieces:tt)*] join($($args:tt)*) $(, $($rest:tt)*)?) => {
+        $crate::build_str!(@munch [$($pieces)* (join($($args)*))] $($($rest)*)?)
+    };
+    (@munch [$($pieces:tt)*] repeat($($args:tt)*) $(, $($rest:tt)*)?) => {
+        $crate::build_str!(@munch [$($pieces)* (repeat($($args)*))] $($($rest)*)?)
+    };
+    (@munch [$($pieces:tt)*] $s:expr $(, $($rest:tt)*)?) => {
+        $crate::build_str!(@munch [$($pieces)* ($s)] $($($rest)*)?)
+    };
+    (@munch []) => {
+        ""
+    };
+    (@munch [$(($($piece:tt)*))*]) => {
+        $crate::__build_str!(0 $(+ $crate::__build_piece!(size $($piece)*))*, |buffer| {
+            let op = 0;
+            $(let op = $crate::__build_piece!(write buffer op $($piece)*);)*
+            op
+        })
+    };
+    ($($pieces:tt)*) => {
+        $crate::build_str!(@munch [] $($pieces)*)
+    };
+}
+
+/// Returns how often `c` occurs in `s`.
+///
+/// ```rust
+/// assert_eq!(const_str_join::count_char("a.b.c", '.'), 2);
+/// assert_eq!(const_str_join::count_char("é-è-é", 'é'), 2);
+/// ```
+pub const fn count_char(s: &str, c: char) -> usize {
+    let mut buffer = [0u8; 4];
+    let encoded = c.encode_utf8(&mut buffer);
+    count_bytes(s.as_bytes(), encoded.as_bytes())
+}
+
+/// Returns whether `c` occurs in `s`.
+///
+/// ```rust
+/// const _: () = assert!(const_str_join::contains_char("café", 'é'));
+/// const _: () = assert!(!const_str_join::contains_char("cafe", 'é'));
+/// ```
+pub const fn contains_char(s: &str, c: char) -> bool {
+    let mut buffer = [0u8; 4];
+    let encoded = c.encode_utf8(&mut buffer).as_bytes();
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes_match_at(bytes, i, encoded) {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+/// Returns whether `a` and `b` are equal.
+///
+/// ```rust
+/// const _: () = assert!(const_str_join::str_eq("abc", "abc"));
+/// const _: () = assert!(!const_str_join::str_eq("abc", "abd"));
+/// ```
+pub const fn str_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len() && bytes_match_at(a.as_bytes(), 0, b.as_bytes())
+}
+
+/// Returns the indices of the first pair of equal elements of `inputs`.
+#[doc(hidden)]
+pub const fn first_duplicate(inputs: &[&str]) -> Option<(usize, usize)> {
+    let mut i = 0;
+    while i < inputs.len() {
+        let mut j = i + 1;
+        while j < inputs.len() {
+            if str_eq(get_str(inputs, i), get_str(inputs, j)) {
+                return Some((i, j));
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Joins `array` with `sep` and fails compilation if `array` contains
+/// the same element more than once.
+///
+/// The error message names the indices of the first duplicate pair.
+///
+/// Example usage:
+/// ```rust
+/// const VARIANTS: &'static str = const_str_join::declare_joined_unique_assert_str!(["Red", "Green", "Blue"], "|");
+/// assert_eq!(VARIANTS, "Red|Green|Blue");
+/// ```
+///
+/// ```rust,compile_fail
+/// const VARIANTS: &'static str = const_str_join::declare_joined_unique_assert_str!(["Red", "Green", "Red"], "|");
+/// ```
+#[macro_export]
+macro_rules! declare_joined_unique_assert_str {
+    ($array:expr, $sep:expr) => {
+        const {
+            const __DUPLICATE: Option<(usize, usize)> = $crate::first_duplicate(&$array);
+            if __DUPLICATE.is_some() {
+                const __PAIR: (usize, usize) = if let Some(pair) = __DUPLICATE {
+                    pair
+                } else {
+                    (0, 0)
+                };
+                panic!(
+                    "{}",
+                    $crate::build_str!(
+                        "duplicate elements at index ",
+                        $crate::__usize_str!(__PAIR.0),
+                        " and ",
+                        $crate::__usize_str!(__PAIR.1)
+                    )
+                );
+            }
+            $crate::const_join!($array, $sep)
+        }
+    };
+}
+
+/// Where [`declare_joined_mode_str!`] places separators.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JoinMode {
+    /// Only between elements: `a,b,c`.
+    Between,
+    /// Between elements and before the first one: `,a,b,c`.
+    Leading,
+    /// Between elements and after the last one: `a,b,c,`.
+    Trailing,
+    /// Between elements and around all of them: `,a,b,c,`.
+    Surrounding,
+}
+
+impl JoinMode {
+    const fn leading(self) -> bool {
+        matches!(self, JoinMode::Leading | JoinMode::Surrounding)
+    }
+
+    const fn trailing(self) -> bool {
+        matches!(self, JoinMode::Trailing | JoinMode::Surrounding)
+    }
+}
+
+#[doc(hidden)]
+pub const fn mode_joined_size(inputs: &[&str], sep: &str, mode: JoinMode) -> usize {
+    if inputs.is_empty() {
+        return 0;
+    }
+    let mut n = required_size(inputs, Some(sep));
+    if mode.leading() {
+        n += sep.len();
+    }
+    if mode.trailing() {
+        n += sep.len();
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_mode_joined(
+    inputs: &[&str],
+    sep: &str,
+    mode: JoinMode,
+    output: &mut [u8],
+) -> usize {
+    if inputs.is_empty() {
+        return 0;
+    }
+    let mut op = 0;
+    if mode.leading() {
+        op = copy_bytes(sep.as_bytes(), output, op);
+    }
+    op = write_joined(inputs, Some(sep), output, op);
+    if mode.trailing() {
+        op = copy_bytes(sep.as_bytes(), output, op);
+    }
+    op
+}
+
+/// Joins `array` with `sep`, placing the separators according to `mode`
+/// (a [`JoinMode`]).
+///
+/// An empty `array` always results in an empty string, no matter the mode.
+///
+/// Example usage:
+/// ```rust
+/// use const_str_join::{declare_joined_mode_str, JoinMode};
+///
+/// const PATH: &'static str = declare_joined_mode_str!(["usr", "bin"], "/", JoinMode::Leading);
+/// assert_eq!(PATH, "/usr/bin");
+/// const LINES: &'static str = declare_joined_mode_str!(["a", "b"], "\n", JoinMode::Trailing);
+/// assert_eq!(LINES, "a\nb\n");
+/// ```
+#[macro_export]
+macro_rules! declare_joined_mode_str {
+    ($array:expr, $sep:expr, $mode:expr) => {
+        const {
+            const __SEP: &str = $crate::__separator!($sep);
+            const __MODE: $crate::JoinMode = $mode;
+            $crate::__build_str!($crate::mode_joined_size(&$array, __SEP, __MODE), |buffer| {
+                $crate::write_mode_joined(&$array, __SEP, __MODE, &mut buffer)
+            })
+        }
+    };
+}
+
+#[doc(hidden)]
+pub const fn write_wrapped(s: &str, width: usize, output: &mut [u8]) -> usize {
+    let bytes = s.as_bytes();
+    let op = copy_bytes(bytes, output, 0);
+    // width (in chars) of the current line
+    let mut line = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\n' => line = 0,
+            b' ' => {
+                let mut end = i + 1;
+                while end < bytes.len() && bytes[end] != b' ' && bytes[end] != b'\n' {
+                    end += 1;
+                }
+                let word = char_count(str_slice(s, i + 1, end));
+                if line > 0 && line + 1 + word > width {
+                    output[i] = b'\n';
+                    line = 0;
+                } else {
+                    line += 1;
+                }
+            }
+            b => {
+                if b & 0b1100_0000 != 0b1000_0000 {
+                    line += 1;
+                }
+            }
+        }
+        i += 1;
+    }
+    op
+}
+
+/// Wraps `s` into lines of at most `width` chars by replacing spaces
+/// with newlines.
+///
+/// Lines are filled greedily. A word that is wider than `width` on its
+/// own is never split, it ends up on a line of its own that overflows
+/// `width`. Existing newlines are kept and start a new line. As only
+/// spaces are replaced the result has the same length as `s`.
+///
+/// Example usage:
+/// ```rust
+/// const TEXT: &'static str = const_str_join::wrap_str!("the quick brown fox", 9);
+/// assert_eq!(TEXT, "the quick\nbrown fox");
+/// ```
+#[macro_export]
+macro_rules! wrap_str {
+    ($s:expr, $width:expr) => {
+        const {
+            const __INPUT: &str = $s;
+            $crate::__build_str!(__INPUT.len(), |buffer| {
+                $crate::write_wrapped(__INPUT, $width, &mut buffer)
+            })
+        }
+    };
+}
+
+const fn assert_gap_count(inputs: &[&str], gaps: usize) {
+    let expected = if inputs.is_empty() {
+        0
+    } else {
+        inputs.len() - 1
+    };
+    assert!(
+        gaps == expected,
+        "there must be exactly one mask entry per gap (array length - 1)"
+    );
+}
+
+#[doc(hidden)]
+pub const fn masked_joined_size(inputs: &[&str], sep: &str, mask: &[bool]) -> usize {
+    assert_gap_count(inputs, mask.len());
+    let mut n = required_size(inputs, None);
+    let mut i = 0;
+    while i < mask.len() {
+        if *get_elem(mask, i) {
+            n += sep.len();
+        }
+        i += 1;
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_masked_joined(
+    inputs: &[&str],
+    sep: &str,
+    mask: &[bool],
+    output: &mut [u8],
+) -> usize {
+    let mut op = 0;
+    let mut i = 0;
+    while i < inputs.len() {
+        if i > 0 && *get_elem(mask, i - 1) {
+            op = copy_bytes(sep.as_bytes(), output, op);
+        }
+        op = copy_bytes(get_str(inputs, i).as_bytes(), output, op);
+        i += 1;
+    }
+    op
+}
+
+/// Joins `array`, emitting `sep` only at the gaps where `mask` is `true`.
+///
+/// `mask[i]` controls the gap between `array[i]` and `array[i + 1]`, so it
+/// must have exactly one entry less than `array`.
+///
+/// Example usage:
+/// ```rust
+/// const S: &'static str = const_str_join::declare_joined_masked_str!(["a", "b", "c"], ",", [true, false]);
+/// assert_eq!(S, "a,bc");
+/// ```
+///
+/// ```rust,compile_fail
+/// const S: &'static str = const_str_join::declare_joined_masked_str!(["a", "b", "c"], ",", [true]);
+/// ```
+#[macro_export]
+macro_rules! declare_joined_masked_str {
+    ($array:expr, $sep:expr, $mask:expr) => {
+        const {
+            const __SEP: &str = $crate::__separator!($sep);
+            $crate::__build_str!(
+                $crate::masked_joined_size(&$array, __SEP, &$mask),
+                |buffer| { $crate::write_masked_joined(&$array, __SEP, &$mask, &mut buffer) }
+            )
+        }
+    };
+}
+
+/// Like [`const_join!`] but prefixes the result with the UTF-8 byte
+/// order mark (`\xEF\xBB\xBF`, `'\u{FEFF}'`).
+///
+/// Example usage:
+/// ```rust
+/// const S: &'static str = const_str_join::declare_joined_bom_str!(["a", "b"], ";");
+/// assert_eq!(S.as_bytes(), b"\xEF\xBB\xBFa;b");
+/// ```
+#[macro_export]
+macro_rules! declare_joined_bom_str {
+    ($array:expr, $sep:expr) => {
+        $crate::build_str!("\u{FEFF}", join($array, $sep))
+    };
+}
+
+const fn get_or<'a>(inputs: &[&'a str], i: usize, fill: &'a str) -> &'a str {
+    if i < inputs.len() {
+        get_str(inputs, i)
+    } else {
+        fill
+    }
+}
+
+#[doc(hidden)]
+pub const fn zip_joined_size(
+    a: &[&str],
+    b: &[&str],
+    pair_sep: &str,
+    sep: &str,
+    fill: &str,
+) -> usize {
+    let pairs = if a.len() > b.len() { a.len() } else { b.len() };
+    let mut n = if pairs == 0 { 0 } else { pairs - 1 } * sep.len();
+    let mut i = 0;
+    while i < pairs {
+        n += get_or(a, i, fill).len() + pair_sep.len() + get_or(b, i, fill).len();
+        i += 1;
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_zip_joined(
+    a: &[&str],
+    b: &[&str],
+    pair_sep: &str,
+    sep: &str,
+    fill: &str,
+    output: &mut [u8],
+) -> usize {
+    let pairs = if a.len() > b.len() { a.len() } else { b.len() };
+    let mut op = 0;
+    let mut i = 0;
+    while i < pairs {
+        if i > 0 {
+            op = copy_bytes(sep.as_bytes(), output, op);
+        }
+        op = copy_bytes(get_or(a, i, fill).as_bytes(), output, op);
+        op = copy_bytes(pair_sep.as_bytes(), output, op);
+        op = copy_bytes(get_or(b, i, fill).as_bytes(), output, op);
+        i += 1;
+    }
+    op
+}
+
+/// Zips `a` and `b` into pairs joined by `pair_sep` and joins the pairs
+/// with `sep`.
+///
+/// If one array is shorter than the other the missing elements are
+/// replaced with `fill`.
+///
+/// Example usage:
+/// ```rust
+/// const S: &'static str = const_str_join::zip_join!(["a", "b", "c"], ["1", "2"], "=", ",", "?");
+/// assert_eq!(S, "a=1,b=2,c=?");
+/// ```
+#[macro_export]
+macro_rules! zip_join {
+    ($a:expr, $b:expr, $pair_sep:expr, $sep:expr, $fill:expr) => {
+        const {
+            const __PAIR_SEP: &str = $crate::__separator!($pair_sep);
+            const __SEP: &str = $crate::__separator!($sep);
+            const __FILL: &str = $fill;
+            $crate::__build_str!(
+                $crate::zip_joined_size(&$a, &$b, __PAIR_SEP, __SEP, __FILL),
+                |buffer| $crate::write_zip_joined(&$a, &$b, __PAIR_SEP, __SEP, __FILL, &mut buffer)
+            )
+        }
+    };
+}
+
+/// Returns the length in bytes of the longest prefix shared by all
+/// `inputs`, never splitting a char. An empty `inputs` yields `0`.
+///
+/// ```rust
+/// use const_str_join::common_prefix_len;
+/// assert_eq!(common_prefix_len(&["src/a.rs", "src/b.rs"]), 4);
+/// assert_eq!(common_prefix_len(&["a", "b"]), 0);
+/// ```
+pub const fn common_prefix_len(inputs: &[&str]) -> usize {
+    if inputs.is_empty() {
+        return 0;
+    }
+    let first = get_str(inputs, 0).as_bytes();
+    let mut len = first.len();
+    let mut i = 1;
+    while i < inputs.len() {
+        let other = get_str(inputs, i).as_bytes();
+        let mut j = 0;
+        while j < len && j < other.len() && first[j] == other[j] {
+            j += 1;
+        }
+        len = j;
+        i += 1;
+    }
+    prev_char_boundary(get_str(inputs, 0), len)
+}
+
+/// Returns the longest prefix shared by all elements of `array`.
+///
+/// Example usage:
+/// ```rust
+/// const ROOT: &'static str = const_str_join::common_prefix_str!(["/usr/bin", "/usr/lib"]);
+/// assert_eq!(ROOT, "/usr/");
+/// ```
+#[macro_export]
+macro_rules! common_prefix_str {
+    ($array:expr) => {
+        const {
+            const __ELEMS: &[&str] = &$array;
+            if __ELEMS.is_empty() {
+                ""
+            } else {
+                $crate::str_slice(
+                    $crate::get_str(__ELEMS, 0),
+                    0,
+                    $crate::common_prefix_len(__ELEMS),
+                )
+            }
+        }
+    };
+}
+
+const fn group_gap_sep<'a>(gap: usize, group: usize, sep: &'a str, group_sep: &'a str) -> &'a str {
+    if (gap + 1).is_multiple_of(group) {
+        group_sep
+    } else {
+        sep
+    }
+}
+
+#[doc(hidden)]
+pub const fn grouped_size(inputs: &[&str], group: usize, sep: &str, group_sep: &str) -> usize {
+    assert!(group > 0, "group size must not be zero");
+    let mut n = required_size(inputs, None);
+    let mut gap = 0;
+    while gap + 1 < inputs.len() {
+        n += group_gap_sep(gap, group, sep, group_sep).len();
+        gap += 1;
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_grouped(
+    inputs: &[&str],
+    group: usize,
+    sep: &str,
+    group_sep: &str,
+    output: &mut [u8],
+) -> usize {
+    let mut op = 0;
+    let mut i = 0;
+    while i < inputs.len() {
+        if i > 0 {
+            op = copy_bytes(
+                group_gap_sep(i - 1, group, sep, group_sep).as_bytes(),
+                output,
+                op,
+            );
+        }
+        op = copy_bytes(get_str(inputs, i).as_bytes(), output, op);
+        i += 1;
+    }
+    op
+}
+
+/// Joins `array` in groups of `size` elements. Elements within a group
+/// are joined with `sep`, groups are joined with `group_sep`.
+///
+/// The last group is shorter if `size` doesn't divide the array length.
+///
+/// Example usage:
+/// ```rust
+/// const S: &'static str = const_str_join::declare_grouped_str!(["1", "2", "3", "4", "5"], 2, "", " ");
+/// assert_eq!(S, "12 34 5");
+/// ```
+#[macro_export]
+macro_rules! declare_grouped_str {
+    ($array:expr, $size:expr, $sep:expr, $group_sep:expr) => {
+        const {
+            const __GROUP: usize = $size;
+            const __SEP: &str = $crate::__separator!($sep);
+            const __GROUP_SEP: &str = $crate::__separator!($group_sep);
+            $crate::__build_str!(
+                $crate::grouped_size(&$array, __GROUP, __SEP, __GROUP_SEP),
+                |buffer| $crate::write_grouped(&$array, __GROUP, __SEP, __GROUP_SEP, &mut buffer)
+            )
+        }
+    };
+}
+
+#[doc(hidden)]
+pub const fn write_replaced_char(s: &str, from: char, to: char, output: &mut [u8]) -> usize {
+    assert!(from.is_ascii() && to.is_ascii(), "both chars must be ASCII");
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        output[i] = if bytes[i] == from as u8 {
+            to as u8
+        } else {
+            bytes[i]
+        };
+        i += 1;
+    }
+    i
+}
+
+/// Replaces every occurrence of the ASCII char `from` in `s` with the
+/// ASCII char `to`.
+///
+/// As both chars are a single byte the length doesn't change, which
+/// makes this cheaper than a general replacement.
+///
+/// Example usage:
+/// ```rust
+/// const PATH: &'static str = const_str_join::replace_char_str!("a.b.c", '.', '/');
+/// assert_eq!(PATH, "a/b/c");
+/// ```
+///
+/// ```rust,compile_fail
+/// const PATH: &'static str = const_str_join::replace_char_str!("a.b.c", '.', '→');
+/// ```
+#[macro_export]
+macro_rules! replace_char_str {
+    ($s:expr, $from:expr, $to:expr) => {
+        const {
+            const __INPUT: &str = $s;
+            $crate::__build_str!(__INPUT.len(), |buffer| {
+                $crate::write_replaced_char(__INPUT, $from, $to, &mut buffer)
+            })
+        }
+    };
+}
+
+/// Walks `s` turning every line ending (`\r\n`, `\r` or `\n`) into `\n` or
+/// `\r\n`, writes to `output` if given and returns the resulting length.
+const fn normalize_newlines(s: &str, windows: bool, mut output: Option<&mut [u8]>) -> usize {
+    let bytes = s.as_bytes();
+    let newline: &[u8] = if windows { b"\r\n" } else { b"\n" };
+    let mut op = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\r' || bytes[i] == b'\n' {
+            if bytes[i] == b'\r' && i + 1 < bytes.len() && bytes[i + 1] == b'\n' {
+                i += 1;
+            }
+            if let Some(output) = &mut output {
+                copy_bytes(newline, output, op);
+            }
+            op += newline.len();
+        } else {
+            if let Some(output) = &mut output {
+                output[op] = bytes[i];
+            }
+            op += 1;
+        }
+        i += 1;
+    }
+    op
+}
+
+#[doc(hidden)]
+pub const fn normalized_newlines_size(s: &str, windows: bool) -> usize {
+    normalize_newlines(s, windows, None)
+}
+
+#[doc(hidden)]
+pub const fn write_normalized_newlines(s: &str, windows: bool, output: &mut [u8]) -> usize {
+    normalize_newlines(s, windows, Some(output))
+}
+
+/// Normalizes all line endings (`\r\n`, lone `\r` and lone `\n`) of `s`.
+///
+/// `Unix` turns all of them into `\n`, `Windows` into `\r\n`.
+///
+/// Example usage:
+/// ```rust
+/// const MIXED: &'static str = "a\r\nb\rc\nd";
+/// const UNIX: &'static str = const_str_join::normalize_newlines_str!(MIXED, Unix);
+/// assert_eq!(UNIX, "a\nb\nc\nd");
+/// const WINDOWS: &'static str = const_str_join::normalize_newlines_str!(MIXED, Windows);
+/// assert_eq!(WINDOWS, "a\r\nb\r\nc\r\nd");
+/// ```
+#[macro_export]
+macro_rules! normalize_newlines_str {
+    (@impl $s:expr, $windows:expr) => {
+        const {
+            const __INPUT: &str = $s;
+            $crate::__build_str!($crate::normalized_newlines_size(__INPUT, $windows), |buffer| {
+                $crate::write_normalized_newlines(__INPUT, $windows, &mut buffer)
+            })
+        }
+    };
+    ($s:expr, Unix) => {
+        $crate::normalize_newlines_str!(@impl $s, false)
+    };
+    ($s:expr, Windows) => {
+        $crate::normalize_newlines_str!(@impl $s, true)
+    };
+}
+
+/// Joins `array` with `sep` after transforming every element with a pair
+/// of user-provided `const fn`s.
+///
+/// * `size_fn: const fn(&str) -> usize` returns the transformed length of an element,
+/// * `write_fn: const fn(&str, &mut [u8]) -> usize` writes the transformed element
+///   to the start of the given buffer and returns the number of bytes written.
+///
+/// Both have to agree on the length and produce valid UTF-8. They are
+/// called directly by the macro, so they have to be named functions.
+///
+/// Example usage:
+/// ```rust
+/// const fn quoted_len(s: &str) -> usize {
+///     s.len() + 2
+/// }
+///
+/// const fn write_quoted(s: &str, out: &mut [u8]) -> usize {
+///     out[0] = b'\'';
+///     let mut i = 0;
+///     while i < s.len() {
+///         out[i + 1] = s.as_bytes()[i];
+///         i += 1;
+///     }
+///     out[i + 1] = b'\'';
+///     i + 2
+/// }
+///
+/// const S: &'static str = const_str_join::declare_joined_map_str!(["a", "b"], ", ", quoted_len, write_quoted);
+/// assert_eq!(S, "'a', 'b'");
+/// ```
+#[macro_export]
+macro_rules! declare_joined_map_str {
+    ($array:expr, $sep:expr, $size_fn:path, $write_fn:path) => {
+        const {
+            const __SEP: &str = $crate::__separator!($sep);
+            const __ELEMS: &[&str] = &$array;
+            $crate::__build_str!(
+                {
+                    let mut n = if __ELEMS.is_empty() {
+                        0
+                    } else {
+                        (__ELEMS.len() - 1) * __SEP.len()
+                    };
+                    let mut i = 0;
+                    while i < __ELEMS.len() {
+                        n += $size_fn($crate::get_str(__ELEMS, i));
+                        i += 1;
+                    }
+                    n
+                },
+                |buffer| {
+                    let mut op = 0;
+                    let mut i = 0;
+                    while i < __ELEMS.len() {
+                        if i > 0 {
+                            op = $crate::copy_bytes(__SEP.as_bytes(), &mut buffer, op);
+                        }
+                        let (_, rest) = buffer.split_at_mut(op);
+                        op += $write_fn($crate::get_str(__ELEMS, i), rest);
+                        i += 1;
+                    }
+                    op
+                }
+            )
+        }
+    };
+}
+
+/// Joins `array` using a separator computed for every gap by `sep_fn`.
+///
+/// `sep_fn` is a `const fn(prev: &str, next: &str, gap: usize, gaps: usize) -> &'static str`
+/// that gets the elements around the gap as well as the index of the gap
+/// and the total number of gaps (`array.len() - 1`), the latter being
+/// required for rules like "`and` before the last element".
+///
+/// The function is called once to compute the size and once more to
+/// write the result, so it has to return the same separator for the
+/// same arguments. It is called directly by the macro and therefore
+/// has to be a named function.
+///
+/// Example usage:
+/// ```rust
+/// const fn oxford(_prev: &str, _next: &str, gap: usize, gaps: usize) -> &'static str {
+///     if gaps == 1 {
+///         " and "
+///     } else if gap + 1 == gaps {
+///         ", and "
+///     } else {
+///         ", "
+///     }
+/// }
+///
+/// const S: &'static str = const_str_join::declare_joined_dyn_sep_str!(["a", "b", "c"], oxford);
+/// assert_eq!(S, "a, b, and c");
+/// ```
+#[macro_export]
+macro_rules! declare_joined_dyn_sep_str {
+    ($array:expr, $sep_fn:path) => {
+        const {
+            const __ELEMS: &[&str] = &$array;
+            $crate::__build_str!(
+                {
+                    let mut n = $crate::required_size(__ELEMS, None);
+                    let mut i = 1;
+                    while i < __ELEMS.len() {
+                        let sep: &str = $sep_fn(
+                            $crate::get_str(__ELEMS, i - 1),
+                            $crate::get_str(__ELEMS, i),
+                            i - 1,
+                            __ELEMS.len() - 1,
+                        );
+                        n += sep.len();
+                        i += 1;
+                    }
+                    n
+                },
+                |buffer| {
+                    let mut op = 0;
+                    let mut i = 0;
+                    while i < __ELEMS.len() {
+                        if i > 0 {
+                            let sep: &str = $sep_fn(
+                                $crate::get_str(__ELEMS, i - 1),
+                                $crate::get_str(__ELEMS, i),
+                                i - 1,
+                                __ELEMS.len() - 1,
+                            );
+                            op = $crate::copy_bytes(sep.as_bytes(), &mut buffer, op);
+                        }
+                        op = $crate::copy_bytes(
+                            $crate::get_str(__ELEMS, i).as_bytes(),
+                            &mut buffer,
+                            op,
+                        );
+                        i += 1;
+                    }
+                    op
+                }
+            )
+        }
+    };
+}
+
+/// Concatenates arrays of `&'static str` into a single array, e.g. to
+/// feed the result into [`const_join!`].
+///
+/// More than two arrays are concatenated from left to right.
+///
+/// Example usage:
+/// ```rust
+/// const BASE: [&'static str; 2] = ["--help", "--version"];
+/// const ALL: [&'static str; 3] = const_str_join::concat_arrays!(BASE, ["--verbose"]);
+/// assert_eq!(ALL, ["--help", "--version", "--verbose"]);
+/// ```
+#[macro_export]
+macro_rules! concat_arrays {
+    ($a:expr $(,)?) => {
+        $a
+    };
+    ($a:expr, $b:expr $(,)?) => {
+        const {
+            const __A: &[&str] = &$a;
+            const __B: &[&str] = &$b;
+            let mut out: [&'static str; __A.len() + __B.len()] = [""; __A.len() + __B.len()];
+            let mut i = 0;
+            while i < __A.len() {
+                out[i] = $crate::get_str(__A, i);
+                i += 1;
+            }
+            while i < out.len() {
+                out[i] = $crate::get_str(__B, i - __A.len());
+                i += 1;
+            }
+            out
+        }
+    };
+    ($a:expr, $b:expr, $($rest:expr),+ $(,)?) => {
+        $crate::concat_arrays!($crate::concat_arrays!($a, $b), $($rest),+)
+    };
+}
+
+/// Diagnostic aid that always fails compilation with an error message
+/// containing the size in bytes `const_join!(array, sep)` would have,
+/// e.g. `evaluation panicked: joined size is 5 bytes`.
+///
+/// Meant to be added temporarily while tuning fixed buffer sizes, e.g.
+/// for the three-argument form of [`joined_array!`].
+///
+/// ```rust,compile_fail
+/// const_str_join::const_assert_joined_size!(["a", "b", "c"], ",");
+/// ```
+#[macro_export]
+macro_rules! const_assert_joined_size {
+    ($array:expr, $sep:expr) => {
+        const _: () = {
+            const __JOINED_SIZE: usize =
+                $crate::required_size(&$array, $crate::non_empty_sep($crate::__separator!($sep)));
+            panic!(
+                "{}",
+                $crate::build_str!(
+                    "joined size is ",
+                    $crate::__usize_str!(__JOINED_SIZE),
+                    " bytes"
+                )
+            );
+        };
+    };
+}
+
+/// Returns the length of the common prefix of `inputs` up to and
+/// including the last occurrence of `delim` within it.
+#[doc(hidden)]
+pub const fn common_delimited_prefix_len(inputs: &[&str], delim: &str) -> usize {
+    if inputs.is_empty() || delim.is_empty() {
+        return 0;
+    }
+    let prefix = common_prefix_len(inputs);
+    let bytes = get_str(inputs, 0).as_bytes();
+    let mut end = prefix;
+    while end >= delim.len() {
+        if bytes_match_at(bytes, end - delim.len(), delim.as_bytes()) {
+            return end;
+        }
+        end -= 1;
+    }
+    0
+}
+
+#[doc(hidden)]
+pub const fn distinct_prefix_size(inputs: &[&str], delim: &str, sep: &str) -> usize {
+    let strip = common_delimited_prefix_len(inputs, delim);
+    let n = required_size(inputs, Some(sep));
+    if inputs.is_empty() {
+        n
+    } else {
+        n - (inputs.len() - 1) * strip
+    }
+}
+
+#[doc(hidden)]
+pub const fn write_distinct_prefix(
+    inputs: &[&str],
+    delim: &str,
+    sep: &str,
+    output: &mut [u8],
+) -> usize {
+    let strip = common_delimited_prefix_len(inputs, delim);
+    let mut op = 0;
+    let mut i = 0;
+    while i < inputs.len() {
+        if i == 0 {
+            op = copy_bytes(get_str(inputs, i).as_bytes(), output, op);
+        } else {
+            op = copy_bytes(sep.as_bytes(), output, op);
+            let (_, rest) = get_str(inputs, i).as_bytes().split_at(strip);
+            op = copy_bytes(rest, output, op);
+        }
+        i += 1;
+    }
+    op
+}
+
+/// Joins `array` with `sep`, removing the namespace shared by all
+/// elements from every element but the first.
+///
+/// The shared namespace is the longest common prefix of all elements,
+/// cut back to end right after the last `delim` within it. If there is
+/// no such `delim` nothing is removed and this is a plain join.
+///
+/// Example usage:
+/// ```rust
+/// const S: &'static str = const_str_join::declare_joined_distinct_prefix_str!(["app::a", "app::b"], "::", ", ");
+/// assert_eq!(S, "app::a, b");
+/// ```
+#[macro_export]
+macro_rules! declare_joined_distinct_prefix_str {
+    ($array:expr, $delim:expr, $sep:expr) => {
+        const {
+            const __DELIM: &str = $crate::__separator!($delim);
+            const __SEP: &str = $crate::__separator!($sep);
+            $crate::__build_str!(
+                $crate::distinct_prefix_size(&$array, __DELIM, __SEP),
+                |buffer| $crate::write_distinct_prefix(&$array, __DELIM, __SEP, &mut buffer)
+            )
+        }
+    };
+}
+
+#[doc(hidden)]
+pub const fn write_title_case(s: &str, output: &mut [u8]) -> usize {
+    let bytes = s.as_bytes();
+    let mut word_start = true;
+    let mut i = 0;
+    while i < bytes.len() {
+        output[i] = if word_start {
+            bytes[i].to_ascii_uppercase()
+        } else {
+            bytes[i]
+        };
+        word_start = bytes[i].is_ascii_whitespace();
+        i += 1;
+    }
+    i
+}
+
+/// Uppercases the first ASCII letter of every word in `s`.
+///
+/// A word starts at the beginning of `s` and after every ASCII
+/// whitespace byte, so multiple spaces simply start the word at the
+/// first non-space. Only the first byte of a word is considered, words
+/// starting with something other than an ASCII letter are left as-is
+/// and the rest of a word is never changed.
+///
+/// Example usage:
+/// ```rust
+/// const TITLE: &'static str = const_str_join::title_case_str!("hello world foo");
+/// assert_eq!(TITLE, "Hello World Foo");
+/// ```
+#[macro_export]
+macro_rules! title_case_str {
+    ($s:expr) => {
+        const {
+            const __INPUT: &str = $s;
+            $crate::__build_str!(__INPUT.len(), |buffer| {
+                $crate::write_title_case(__INPUT, &mut buffer)
+            })
+        }
+    };
+}
+
+/// What the sized forms of [`joined_array!`] and [`const_join!`] do when
+/// the joined string doesn't have exactly the requested size.
+///
+/// Sizes that don't fit the policy are rejected at compile time:
+/// ```compile_fail
+/// use const_str_join::{joined_array, SizePolicy};
+/// let s: [u8; 4] = joined_array!(["ab", "cd"], ",", 4, SizePolicy::Pad);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SizePolicy {
+    /// The joined string must have exactly the requested size, anything
+    /// else fails to compile.
+    Exact,
+    /// The joined string may be shorter than the requested size, the
+    /// remaining bytes are zero-filled. Longer strings fail to compile.
+    /// Only supported by [`joined_array!`].
+    Pad,
+    /// Writing stops at the requested size. The string form cuts at the
+    /// last char boundary before it, shorter strings are zero-filled in
+    /// the array form just like with [`SizePolicy::Pad`].
+    Truncate,
+}
+
+/// Returns byte `i` of `inputs` joined with `sep`.
+#[doc(hidden)]
+pub const fn joined_byte_at(inputs: &[&str], sep: Option<&str>, mut i: usize) -> u8 {
+    let mut n = 0;
+    while n < inputs.len() {
+        if n > 0
+            && let Some(sep) = sep
+        {
+            if i < sep.len() {
+                return get_byte(sep.as_bytes(), i);
+            }
+            i -= sep.len();
+        }
+        if i < get_str(inputs, n).len() {
+            return get_byte(get_str(inputs, n).as_bytes(), i);
+        }
+        i -= get_str(inputs, n).len();
+        n += 1;
+    }
+    panic!("index out of bounds of the joined string");
+}
+
+/// Returns how many bytes of the joined string end up in a buffer of
+/// `size` bytes. `str_form` cuts at char boundaries and rejects padding.
+#[doc(hidden)]
+pub const fn policy_joined_size(
+    inputs: &[&str],
+    sep: Option<&str>,
+    size: usize,
+    policy: SizePolicy,
+    str_form: bool,
+) -> usize {
+    let joined = required_size(inputs, sep);
+    match policy {
+        SizePolicy::Exact => {
+            assert!(
+                joined == size,
+                "joined size doesn't match the requested size"
+            );
+            size
+        }
+        SizePolicy::Pad => {
+            assert!(
+                !str_form,
+                "SizePolicy::Pad is only supported by joined_array!"
+            );
+            assert!(joined <= size, "joined string exceeds the requested size");
+            joined
+        }
+        SizePolicy::Truncate if joined <= size => joined,
+        SizePolicy::Truncate if !str_form => size,
+        SizePolicy::Truncate => {
+            let mut end = size;
+            // the first byte that doesn't fit must start a new char
+            while joined_byte_at(inputs, sep, end) & 0b1100_0000 == 0b1000_0000 {
+                end -= 1;
+            }
+            end
+        }
+    }
+}
+
+/// Copies as much of `src` as fits into `dest` starting at `offset`.
+const fn copy_bytes_up_to_len(src: &[u8], dest: &mut [u8], offset: usize) -> usize {
+    let room = dest.len() - offset;
+    let src = if src.len() > room {
+        src.split_at(room).0
+    } else {
+        src
+    };
+    copy_bytes(src, dest, offset)
+}
+
+/// Writes as much of `inputs` joined with `sep` as fits into `output`
+/// and returns the number of bytes written, the remaining bytes are
+/// left untouched.
+#[doc(hidden)]
+pub const fn write_policy_joined(inputs: &[&str], sep: Option<&str>, output: &mut [u8]) -> usize {
+    let mut offset = 0;
+    let mut n = 0;
+    while n < inputs.len() && offset < output.len() {
+        if n > 0
+            && let Some(sep) = sep
+        {
+            offset = copy_bytes_up_to_len(sep.as_bytes(), output, offset);
+        }
+        offset = copy_bytes_up_to_len(get_str(inputs, n).as_bytes(), output, offset);
+        n += 1;
+    }
+    offset
+}
+
+#[doc(hidden)]
+pub const fn write_rot13(s: &str, output: &mut [u8]) -> usize {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        output[i] = match bytes[i] {
+            b @ b'a'..=b'z' => (b - b'a' + 13) % 26 + b'a',
+            b @ b'A'..=b'Z' => (b - b'A' + 13) % 26 + b'A',
+            b => b,
+        };
+        i += 1;
+    }
+    i
+}
+
+/// Rotates every ASCII letter in `s` by 13 places, leaving all other
+/// chars untouched.
+///
+/// Applying it twice yields the original string.
+///
+/// Example usage:
+/// ```rust
+/// const HIDDEN: &'static str = const_str_join::rot13_str!("Hello");
+/// assert_eq!(HIDDEN, "Uryyb");
+/// assert_eq!(const_str_join::rot13_str!(HIDDEN), "Hello");
+/// ```
+#[macro_export]
+macro_rules! rot13_str {
+    ($s:expr) => {
+        const {
+            const __INPUT: &str = $s;
+            $crate::__build_str!(__INPUT.len(), |buffer| $crate::write_rot13(
+                __INPUT,
+                &mut buffer
+            ))
+        }
+    };
+}
+
+#[doc(hidden)]
+pub const fn cycle_sep_size(inputs: &[&str], seps: &[&str]) -> usize {
+    assert!(!seps.is_empty(), "separator cycle must not be empty");
+    let mut n = required_size(inputs, None);
+    let mut gap = 0;
+    while gap + 1 < inputs.len() {
+        n += get_str(seps, gap % seps.len()).len();
+        gap += 1;
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_cycle_sep(inputs: &[&str], seps: &[&str], output: &mut [u8]) -> usize {
+    let mut op = 0;
+    let mut i = 0;
+    while i < inputs.len() {
+        if i > 0 {
+            op = copy_bytes(get_str(seps, (i - 1) % seps.len()).as_bytes(), output, op);
+        }
+        op = copy_bytes(get_str(inputs, i).as_bytes(), output, op);
+        i += 1;
+    }
+    op
+}
+
+/// Joins `array` cycling through the separators in `seps`, gap `i` uses
+/// `seps[i % seps.len()]`.
+///
+/// `seps` must not be empty.
+///
+/// Example usage:
+/// ```rust
+/// const S: &'static str = const_str_join::declare_joined_cycle_sep_str!(["a", "b", "c", "d"], [",", ";"]);
+/// assert_eq!(S, "a,b;c,d");
+/// ```
+///
+/// ```compile_fail
+/// const S: &'static str = const_str_join::declare_joined_cycle_sep_str!(["a", "b"], []);
+/// ```
+#[macro_export]
+macro_rules! declare_joined_cycle_sep_str {
+    ($array:expr, $seps:expr) => {
+        const {
+            const __SEPS: &[&str] = &$seps;
+            $crate::__build_str!($crate::cycle_sep_size(&$array, __SEPS), |buffer| {
+                $crate::write_cycle_sep(&$array, __SEPS, &mut buffer)
+            })
+        }
+    };
+}
+
+/// Returns the length of the `//` comment starting at `bytes[i]` up to,
+/// but excluding, the end of the line, or `0` if there is none.
+#[doc(hidden)]
+pub const fn line_comment_len(bytes: &[u8], i: usize) -> usize {
+    if i + 1 >= bytes.len() || bytes[i] != b'/' || bytes[i + 1] != b'/' {
+        return 0;
+    }
+    let mut j = i + 2;
+    while j < bytes.len() && bytes[j] != b'\n' {
+        j += 1;
+    }
+    j - i
+}
+
+#[doc(hidden)]
+pub const fn strip_comments_size(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let mut n = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let skip = line_comment_len(bytes, i);
+        if skip > 0 {
+            i += skip;
+        } else {
+            n += 1;
+            i += 1;
+        }
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_strip_comments(s: &str, output: &mut [u8]) -> usize {
+    let bytes = s.as_bytes();
+    let mut op = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let skip = line_comment_len(bytes, i);
+        if skip > 0 {
+            i += skip;
+        } else {
+            output[op] = bytes[i];
+            op += 1;
+            i += 1;
+        }
+    }
+    op
+}
+
+/// Removes `//` line comments from `s`.
+///
+/// Everything from `//` up to the end of the line is dropped, the line
+/// break itself as well as any whitespace before the comment are kept.
+///
+/// This is purely textual: a `//` inside a string literal (`"http://"`)
+/// starts a comment just the same and block comments (`/* */`) are left
+/// alone, so only use it on sources where that can't happen.
+///
+/// Example usage:
+/// ```rust
+/// const SRC: &'static str = const_str_join::strip_comments_str!("let x = 1; // one\nlet y = 2;");
+/// assert_eq!(SRC, "let x = 1; \nlet y = 2;");
+/// ```
+#[macro_export]
+macro_rules! strip_comments_str {
+    ($s:expr) => {
+        const {
+            const __INPUT: &str = $s;
+            $crate::__build_str!($crate::strip_comments_size(__INPUT), |buffer| {
+                $crate::write_strip_comments(__INPUT, &mut buffer)
+            })
+        }
+    };
+}
+
+/// Returns the length of `buf` without its trailing NUL bytes.
+///
+/// Only the NUL bytes at the very end are dropped, NUL bytes followed by
+/// anything else are part of the content.
+///
+/// ```rust
+/// use const_str_join::trimmed_nul_len;
+/// assert_eq!(trimmed_nul_len(b"abc\0\0"), 3);
+/// assert_eq!(trimmed_nul_len(b"a\0c\0"), 3);
+/// assert_eq!(trimmed_nul_len(b"\0\0"), 0);
+/// ```
+pub const fn trimmed_nul_len(buf: &[u8]) -> usize {
+    let mut len = buf.len();
+    while len > 0 && buf[len - 1] == 0 {
+        len -= 1;
+    }
+    len
+}
+
+/// Returns the content of a byte buffer, such as one of the padded forms
+/// of [`joined_array!`], without its trailing NUL bytes as a
+/// `&'static str`.
+///
+/// See [`trimmed_nul_len`] for which bytes are dropped.
+///
+/// Example usage:
+/// ```rust
+/// use const_str_join::{joined_array, trim_nul_str, SizePolicy};
+///
+/// const S: &'static str = trim_nul_str!(joined_array!(["a", "b"], ",", 16, SizePolicy::Pad));
+/// assert_eq!(S, "a,b");
+/// ```
+#[macro_export]
+macro_rules! trim_nul_str {
+    ($buf:expr) => {
+        const {
+            const __BUF: &[u8] = &$buf;
+            const __TRIMMED: &[u8] = __BUF.split_at($crate::trimmed_nul_len(__BUF)).0;
+            if let Ok(v) = core::str::from_utf8(__TRIMMED) {
+                v
+            } else {
+                panic!("buffer isn't a valid utf8 string");
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+pub const fn joined_bytes_size(inputs: &[&[u8]], sep: &[u8]) -> usize {
+    let mut n = 0;
+    let mut i = 0;
+    while i < inputs.len() {
+        if i > 0 {
+            n += sep.len();
+        }
+        n += get_elem(inputs, i).len();
+        i += 1;
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_joined_bytes(inputs: &[&[u8]], sep: &[u8], output: &mut [u8]) -> usize {
+    let mut op = 0;
+    let mut i = 0;
+    while i < inputs.len() {
+        if i > 0 {
+            op = copy_bytes(sep, output, op);
+        }
+        op = copy_bytes(get_elem::<&[u8]>(inputs, i), output, op);
+        i += 1;
+    }
+    op
+}
+
+/// Joins byte-slice fragments with a byte-slice separator and returns the
+/// result as `&'static str`.
+///
+/// The fragments don't have to be valid UTF-8 on their own, only the
+/// joined result is validated. If it isn't valid, compilation fails with
+/// the index of the first offending byte.
+///
+/// Example usage:
+/// ```rust
+/// const S: &'static str = const_str_join::declare_joined_str_from_bytes!([b"ab", b"c"], b", ");
+/// assert_eq!(S, "ab, c");
+/// // only the joined result has to be valid
+/// const U: &'static str = const_str_join::declare_joined_str_from_bytes!([b"\xc3", b"\xa4"], b"");
+/// assert_eq!(U, "ä");
+/// ```
+///
+/// ```compile_fail
+/// const S: &'static str = const_str_join::declare_joined_str_from_bytes!([b"ok", b"\xff"], b",");
+/// ```
+#[macro_export]
+macro_rules! declare_joined_str_from_bytes {
+    ([$($elem:expr),* $(,)?], $sep:expr) => {
+        const {
+            // the annotated type turns byte string literals of different
+            // lengths into slices
+            const __ELEMS: &[&[u8]] = &[$($elem),*];
+            $crate::declare_joined_str_from_bytes!(*__ELEMS, $sep)
+        }
+    };
+    ($array:expr, $sep:expr) => {
+        const {
+            const __SEP: &[u8] = $sep;
+            const __SIZE: usize = $crate::joined_bytes_size(&$array, __SEP);
+            const __BYTES: [u8; __SIZE] = {
+                let mut buffer = [0u8; __SIZE];
+                $crate::write_joined_bytes(&$array, __SEP, &mut buffer);
+                buffer
+            };
+            $crate::__assert_none_at!(
+                $crate::first_invalid_index(&__BYTES),
+                "joined bytes aren't valid utf8, invalid byte at index "
+            );
+            static __STORAGE: [u8; __SIZE] = __BYTES;
+            if let Ok(v) = core::str::from_utf8(&__STORAGE) {
+                v
+            } else {
+                panic!("joined array isn't a valid utf8 string");
+            }
+        }
+    };
+}
+
+/// Returns `word` if `count` is `1` and `word` followed by an `s`
+/// otherwise.
+///
+/// Only the regular English plural is covered, use
+/// [`count_noun_str!`] for irregular ones.
+///
+/// Example usage:
+/// ```rust
+/// const FILES: usize = 3;
+/// const S: &'static str = const_str_join::pluralize_str!("file", FILES);
+/// assert_eq!(S, "files");
+/// assert_eq!(const_str_join::pluralize_str!("file", 1), "file");
+/// ```
+#[macro_export]
+macro_rules! pluralize_str {
+    ($word:expr, $count:expr) => {
+        const {
+            const __WORD: &str = $word;
+            if $count == 1 {
+                __WORD
+            } else {
+                $crate::const_join!([__WORD, "s"], "")
+            }
+        }
+    };
+}
+
+/// Returns `count` followed by a space and either `singular` (if `count`
+/// is `1`) or `plural`.
+///
+/// Example usage:
+/// ```rust
+/// use const_str_join::count_noun_str;
+///
+/// assert_eq!(count_noun_str!(1, "item", "items"), "1 item");
+/// assert_eq!(count_noun_str!(3, "item", "items"), "3 items");
+/// assert_eq!(count_noun_str!(2, "child", "children"), "2 children");
+/// ```
+#[macro_export]
+macro_rules! count_noun_str {
+    ($count:expr, $singular:expr, $plural:expr) => {
+        const {
+            const __COUNT: usize = $count;
+            const __NOUN: &str = if __COUNT == 1 { $singular } else { $plural };
+            $crate::const_join!([$crate::__usize_str!(__COUNT), __NOUN], " ")
+        }
+    };
+}
+
+#[doc(hidden)]
+pub const fn joined_numbers_size(numbers: &[usize], sep: &str) -> usize {
+    let mut n = 0;
+    let mut i = 0;
+    while i < numbers.len() {
+        if i > 0 {
+            n += sep.len();
+        }
+        n += decimal_len(*get_elem(numbers, i) as u64);
+        i += 1;
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_joined_numbers(numbers: &[usize], sep: &str, output: &mut [u8]) -> usize {
+    let mut op = 0;
+    let mut i = 0;
+    while i < numbers.len() {
+        if i > 0 {
+            op = copy_bytes(sep.as_bytes(), output, op);
+        }
+        op = write_decimal(*get_elem(numbers, i) as u64, output, op);
+        i += 1;
+    }
+    op
+}
+
+#[doc(hidden)]
+pub const fn joined_signed_numbers_size(numbers: &[i64], sep: &str) -> usize {
+    let mut n = 0;
+    let mut i = 0;
+    while i < numbers.len() {
+        if i > 0 {
+            n += sep.len();
+        }
+        if *get_elem(numbers, i) < 0 {
+            n += 1;
+        }
+        n += decimal_len(get_elem(numbers, i).unsigned_abs());
+        i += 1;
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_joined_signed_numbers(numbers: &[i64], sep: &str, output: &mut [u8]) -> usize {
+    let mut op = 0;
+    let mut i = 0;
+    while i < numbers.len() {
+        if i > 0 {
+            op = copy_bytes(sep.as_bytes(), output, op);
+        }
+        if *get_elem(numbers, i) < 0 {
+            op = copy_bytes(b"-", output, op);
+        }
+        op = write_decimal(get_elem(numbers, i).unsigned_abs(), output, op);
+        i += 1;
+    }
+    op
+}
+
+/// Joins the decimal representations of an array of `usize` with `sep`.
+///
+/// See [`join_signed_numbers_str!`] for `i64` arrays.
+///
+/// Example usage:
+/// ```rust
+/// const PORTS: [usize; 3] = [80, 443, 8080];
+/// const S: &'static str = const_str_join::join_numbers_str!(PORTS, ",");
+/// assert_eq!(S, "80,443,8080");
+/// ```
+#[macro_export]
+macro_rules! join_numbers_str {
+    ($array:expr, $sep:expr) => {
+        const {
+            const __NUMBERS: &[usize] = &$array;
+            const __SEP: &str = $crate::__separator!($sep);
+            $crate::__build_str!($crate::joined_numbers_size(__NUMBERS, __SEP), |buffer| {
+                $crate::write_joined_numbers(__NUMBERS, __SEP, &mut buffer)
+            })
+        }
+    };
+}
+
+/// Joins the decimal representations of an array of `i64` with `sep`,
+/// negative numbers are prefixed with `-`.
+///
+/// Example usage:
+/// ```rust
+/// const S: &'static str = const_str_join::join_signed_numbers_str!([-1, 0, 42], ' ');
+/// assert_eq!(S, "-1 0 42");
+/// ```
+#[macro_export]
+macro_rules! join_signed_numbers_str {
+    ($array:expr, $sep:expr) => {
+        const {
+            const __NUMBERS: &[i64] = &$array;
+            const __SEP: &str = $crate::__separator!($sep);
+            $crate::__build_str!(
+                $crate::joined_signed_numbers_size(__NUMBERS, __SEP),
+                |buffer| { $crate::write_joined_signed_numbers(__NUMBERS, __SEP, &mut buffer) }
+            )
+        }
+    };
+}
+
+/// Configuration of [`declare_fancy_join_str!`].
+///
+/// Start from [`JoinOptions::new`] and override the fields you need:
+/// ```rust
+/// use const_str_join::JoinOptions;
+///
+/// const OPTS: JoinOptions = JoinOptions { sep: ", ", prefix: "[", suffix: "]", ..JoinOptions::new() };
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct JoinOptions {
+    /// Separator between elements.
+    pub sep: &'static str,
+    /// Written before everything else.
+    pub prefix: &'static str,
+    /// Written after everything else.
+    pub suffix: &'static str,
+    /// Whether `sep` is also written before the first element.
+    pub leading: bool,
+    /// Whether `sep` is also written after the last element.
+    pub trailing: bool,
+}
+
+impl JoinOptions {
+    /// Options that join without any separator, affix or leading and
+    /// trailing separators.
+    pub const fn new() -> Self {
+        JoinOptions {
+            sep: "",
+            prefix: "",
+            suffix: "",
+            leading: false,
+            trailing: false,
+        }
+    }
+
+    const fn mode(&self) -> JoinMode {
+        match (self.leading, self.trailing) {
+            (false, false) => JoinMode::Between,
+            (true, false) => JoinMode::Leading,
+            (false, true) => JoinMode::Trailing,
+            (true, true) => JoinMode::Surrounding,
+        }
+    }
+}
+
+impl Default for JoinOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[doc(hidden)]
+pub const fn fancy_joined_size(inputs: &[&str], opts: JoinOptions) -> usize {
+    opts.prefix.len() + mode_joined_size(inputs, opts.sep, opts.mode()) + opts.suffix.len()
+}
+
+#[doc(hidden)]
+pub const fn write_fancy_joined(inputs: &[&str], opts: JoinOptions, output: &mut [u8]) -> usize {
+    let mut op = copy_bytes(opts.prefix.as_bytes(), output, 0);
+    let (_, rest) = output.split_at_mut(op);
+    op += write_mode_joined(inputs, opts.sep, opts.mode(), rest);
+    copy_bytes(opts.suffix.as_bytes(), output, op)
+}
+
+/// Joins `array` as configured by `opts`, a [`JoinOptions`].
+///
+/// The result is `prefix`, then the elements separated by `sep` (with
+/// an additional `sep` before and after them if `leading` and `trailing`
+/// are set) and finally `suffix`. Just like with [`JoinMode`] an empty
+/// `array` writes no separators at all.
+///
+/// Example usage:
+/// ```rust
+/// use const_str_join::{declare_fancy_join_str, JoinOptions};
+///
+/// const LIST: JoinOptions = JoinOptions { sep: ", ", prefix: "[", suffix: "]", ..JoinOptions::new() };
+/// const S: &'static str = declare_fancy_join_str!(["a", "b"], LIST);
+/// assert_eq!(S, "[a, b]");
+///
+/// const TABLE: JoinOptions = JoinOptions { sep: "|", leading: true, trailing: true, ..JoinOptions::new() };
+/// const ROW: &'static str = declare_fancy_join_str!(["a", "b"], TABLE);
+/// assert_eq!(ROW, "|a|b|");
+/// ```
+#[macro_export]
+macro_rules! declare_fancy_join_str {
+    ($array:expr, $opts:expr) => {
+        const {
+            const __OPTS: $crate::JoinOptions = $opts;
+            $crate::__build_str!($crate::fancy_joined_size(&$array, __OPTS), |buffer| {
+                $crate::write_fancy_joined(&$array, __OPTS, &mut buffer)
+            })
+        }
+    };
+}
+
+const fn expand_tabs(s: &str, width: usize, mut output: Option<&mut [u8]>) -> usize {
+    assert!(width > 0, "tab width must not be zero");
+    let bytes = s.as_bytes();
+    let mut op = 0;
+    // column (in chars) of the current line
+    let mut column = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\t' {
+            let spaces = width - column % width;
+            if let Some(output) = &mut output {
+                let mut n = 0;
+                while n < spaces {
+                    output[op + n] = b' ';
+                    n += 1;
+                }
+            }
+            op += spaces;
+            column += spaces;
+        } else {
+            if let Some(output) = &mut output {
+                output[op] = bytes[i];
+            }
+            op += 1;
+            if bytes[i] == b'\n' {
+                column = 0;
+            } else if bytes[i] & 0b1100_0000 != 0b1000_0000 {
+                column += 1;
+            }
+        }
+        i += 1;
+    }
+    op
+}
+
+#[doc(hidden)]
+pub const fn expanded_tabs_size(s: &str, width: usize) -> usize {
+    expand_tabs(s, width, None)
+}
+
+#[doc(hidden)]
+pub const fn write_expanded_tabs(s: &str, width: usize, output: &mut [u8]) -> usize {
+    expand_tabs(s, width, Some(output))
+}
+
+/// Replaces every tab in `s` with spaces up to the next column that is a
+/// multiple of `width`.
+///
+/// Columns are counted in chars and start over after every `\n`.
+///
+/// Example usage:
+/// ```rust
+/// const S: &'static str = const_str_join::expand_tabs_str!("a\tb\n\tc", 4);
+/// assert_eq!(S, "a   b\n    c");
+/// ```
+#[macro_export]
+macro_rules! expand_tabs_str {
+    ($s:expr, $width:expr) => {
+        const {
+            const __INPUT: &str = $s;
+            const __WIDTH: usize = $width;
+            $crate::__build_str!($crate::expanded_tabs_size(__INPUT, __WIDTH), |buffer| {
+                $crate::write_expanded_tabs(__INPUT, __WIDTH, &mut buffer)
+            })
+        }
+    };
+}
+
+#[doc(hidden)]
+pub const fn length_prefixed_size(inputs: &[&str]) -> usize {
+    let mut n = 0;
+    let mut i = 0;
+    while i < inputs.len() {
+        n += decimal_len(get_str(inputs, i).len() as u64) + get_str(inputs, i).len();
+        i += 1;
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_length_prefixed(inputs: &[&str], output: &mut [u8]) -> usize {
+    let mut op = 0;
+    let mut i = 0;
+    while i < inputs.len() {
+        op = write_decimal(get_str(inputs, i).len() as u64, output, op);
+        op = copy_bytes(get_str(inputs, i).as_bytes(), output, op);
+        i += 1;
+    }
+    op
+}
+
+/// Concatenates `array` with every element prefixed by its length in
+/// bytes, written in decimal.
+///
+/// An empty element is written as just `0`.
+///
+/// Example usage:
+/// ```rust
+/// const S: &'static str = const_str_join::declare_length_prefixed_str!(["ab", "", "cde"]);
+/// assert_eq!(S, "2ab03cde");
+/// ```
+#[macro_export]
+macro_rules! declare_length_prefixed_str {
+    ($array:expr) => {
+        $crate::__build_str!($crate::length_prefixed_size(&$array), |buffer| {
+            $crate::write_length_prefixed(&$array, &mut buffer)
+        })
+    };
+}
+
+/// Joins `array` with `sep` while enclosing every element in `open` and
+/// `close`.
+///
+/// This is [`declare_each_affixed_join_str!`] under a name that reads
+/// better for brackets, with empty brackets it is a plain join.
+///
+/// Example usage:
+/// ```rust
+/// const S: &'static str = const_str_join::declare_bracketed_join_str!(["a", "b"], ",", "(", ")");
+/// assert_eq!(S, "(a),(b)");
+/// ```
+#[macro_export]
+macro_rules! declare_bracketed_join_str {
+    ($array:expr, $sep:expr, $open:expr, $close:expr) => {
+        $crate::declare_each_affixed_join_str!($array, $sep, $open, $close)
+    };
+}
+
+/// Returns the smallest number of leading spaces and tabs of all lines
+/// in `s` that contain anything else.
+#[doc(hidden)]
+pub const fn common_indent(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let mut min = usize::MAX;
+    let mut i = 0;
+    while i < bytes.len() {
+        let mut indent = 0;
+        while i + indent < bytes.len() && matches!(bytes[i + indent], b' ' | b'\t') {
+            indent += 1;
+        }
+        i += indent;
+        if i < bytes.len() && bytes[i] != b'\n' && indent < min {
+            min = indent;
+        }
+        while i < bytes.len() && bytes[i] != b'\n' {
+            i += 1;
+        }
+        i += 1;
+    }
+    if min == usize::MAX { 0 } else { min }
+}
+
+const fn dedent(s: &str, mut output: Option<&mut [u8]>) -> usize {
+    let bytes = s.as_bytes();
+    let indent = common_indent(s);
+    let mut op = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        // skip the indentation, blank lines may have less of it
+        let mut skipped = 0;
+        while skipped < indent && i < bytes.len() && matches!(bytes[i], b' ' | b'\t') {
+            skipped += 1;
+            i += 1;
+        }
+        while i < bytes.len() {
+            if let Some(output) = &mut output {
+                output[op] = bytes[i];
+            }
+            op += 1;
+            i += 1;
+            if bytes[i - 1] == b'\n' {
+                break;
+            }
+        }
+    }
+    op
+}
+
+#[doc(hidden)]
+pub const fn dedented_size(s: &str) -> usize {
+    dedent(s, None)
+}
+
+#[doc(hidden)]
+pub const fn write_dedented(s: &str, output: &mut [u8]) -> usize {
+    dedent(s, Some(output))
+}
+
+/// Removes the indentation all lines of `s` have in common.
+///
+/// The indentation is made of spaces and tabs, each of them counts as a
+/// single byte of indentation no matter how they are mixed. Lines that
+/// only consist of spaces and tabs don't contribute to the common
+/// indentation, they are kept with at most that much of it removed.
+///
+/// Example usage:
+/// ```rust
+/// const S: &'static str = const_str_join::dedent_str!("    fn f() {\n        g();\n\n    }");
+/// assert_eq!(S, "fn f() {\n    g();\n\n}");
+/// ```
+#[macro_export]
+macro_rules! dedent_str {
+    ($s:expr) => {
+        const {
+            const __INPUT: &str = $s;
+            $crate::__build_str!($crate::dedented_size(__INPUT), |buffer| {
+                $crate::write_dedented(__INPUT, &mut buffer)
+            })
+        }
+    };
+}
+
+/// Returns the index of the first line of `s` that has more than `width`
+/// chars, or `None` if all of them fit.
+#[doc(hidden)]
+pub const fn first_wide_line(s: &str, width: usize) -> Option<usize> {
+    let bytes = s.as_bytes();
+    let mut line = 0;
+    let mut chars = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\n' {
+            line += 1;
+            chars = 0;
+        } else if bytes[i] & 0b1100_0000 != 0b1000_0000 {
+            chars += 1;
+            if chars > width {
+                return Some(line);
+            }
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Joins `array` with newlines just like [`declare_lines!`] and fails
+/// compilation if any line of the result is wider than `width` chars.
+///
+/// Elements containing newlines are split into several lines, the error
+/// reports the index of the offending line in the joined string.
+///
+/// Example usage:
+/// ```rust
+/// const TEXT: &'static str = const_str_join::declare_lines_maxwidth!(["short", "lines"], 10);
+/// assert_eq!(TEXT, "short\nlines");
+/// ```
+///
+/// ```compile_fail
+/// const TEXT: &'static str = const_str_join::declare_lines_maxwidth!(["short", "much too long"], 10);
+/// ```
+#[macro_export]
+macro_rules! declare_lines_maxwidth {
+    ($array:expr, $width:expr) => {
+        const {
+            const __LINES: &str = $crate::declare_lines!($array);
+            $crate::__assert_none_at!(
+                $crate::first_wide_line(__LINES, $width),
+                "line exceeds the maximum width, line index "
+            );
+            __LINES
+        }
+    };
+}
+
+#[doc(hidden)]
+pub const fn reversed_words_size(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let mut n = 0;
+    let mut words = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_whitespace() {
+            if i == 0 || bytes[i - 1].is_ascii_whitespace() {
+                words += 1;
+            }
+            n += 1;
+        }
+        i += 1;
+    }
+    if words > 0 { n + words - 1 } else { 0 }
+}
+
+#[doc(hidden)]
+pub const fn write_reversed_words(s: &str, output: &mut [u8]) -> usize {
+    let bytes = s.as_bytes();
+    let mut op = 0;
+    let mut end = bytes.len();
+    while end > 0 {
+        if bytes[end - 1].is_ascii_whitespace() {
+            end -= 1;
+            continue;
+        }
+        let mut start = end;
+        while start > 0 && !bytes[start - 1].is_ascii_whitespace() {
+            start -= 1;
+        }
+        if op > 0 {
+            output[op] = b' ';
+            op += 1;
+        }
+        let (head, _) = bytes.split_at(end);
+        let (_, word) = head.split_at(start);
+        op = copy_bytes(word, output, op);
+        end = start;
+    }
+    op
+}
+
+/// Reverses the order of the words in `s`.
+///
+/// Words are separated by ASCII whitespace. The result separates them by
+/// a single space no matter how they were separated before, leading and
+/// trailing whitespace is dropped.
+///
+/// Example usage:
+/// ```rust
+/// const S: &'static str = const_str_join::reverse_words_str!("the quick brown fox");
+/// assert_eq!(S, "fox brown quick the");
+/// ```
+#[macro_export]
+macro_rules! reverse_words_str {
+    ($s:expr) => {
+        const {
+            const __INPUT: &str = $s;
+            $crate::__build_str!($crate::reversed_words_size(__INPUT), |buffer| {
+                $crate::write_reversed_words(__INPUT, &mut buffer)
+            })
+        }
+    };
+}
+
+#[doc(hidden)]
+pub const fn doc_block_size(lines: &[&str]) -> usize {
+    let mut n = 0;
+    let mut i = 0;
+    while i < lines.len() {
+        if i > 0 {
+            n += 1;
+        }
+        n += if get_str(lines, i).is_empty() {
+            3
+        } else {
+            4 + get_str(lines, i).len()
+        };
+        i += 1;
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_doc_block(lines: &[&str], output: &mut [u8]) -> usize {
+    let mut op = 0;
+    let mut i = 0;
+    while i < lines.len() {
+        if i > 0 {
+            op = copy_bytes(b"\n", output, op);
+        }
+        if get_str(lines, i).is_empty() {
+            op = copy_bytes(b"///", output, op);
+        } else {
+            op = copy_bytes(b"/// ", output, op);
+            op = copy_bytes(get_str(lines, i).as_bytes(), output, op);
+        }
+        i += 1;
+    }
+    op
+}
+
+/// Turns `array` into a `///` doc comment with one element per line.
+///
+/// Every line is prefixed with `/// `, except for empty ones which
+/// become just `///` so the block doesn't end up with trailing
+/// whitespace. There is no trailing newline.
+///
+/// Example usage:
+/// ```rust
+/// const DOC: &'static str = const_str_join::declare_doc_block!(["Line one.", "", "Line two."]);
+/// assert_eq!(DOC, "/// Line one.\n///\n/// Line two.");
+/// ```
+#[macro_export]
+macro_rules! declare_doc_block {
+    ($array:expr) => {
+        $crate::__build_str!($crate::doc_block_size(&$array), |buffer| {
+            $crate::write_doc_block(&$array, &mut buffer)
+        })
+    };
+}
+
+/// Returns the length in bytes of the longest suffix shared by all
+/// `inputs`, never splitting a char. An empty `inputs` yields `0`.
+///
+/// ```rust
+/// use const_str_join::common_suffix_len;
+/// assert_eq!(common_suffix_len(&["main.rs", "lib.rs"]), 3);
+/// assert_eq!(common_suffix_len(&["a", "b"]), 0);
+/// ```
+pub const fn common_suffix_len(inputs: &[&str]) -> usize {
+    if inputs.is_empty() {
+        return 0;
+    }
+    let first = get_str(inputs, 0).as_bytes();
+    let mut len = first.len();
+    let mut i = 1;
+    while i < inputs.len() {
+        let other = get_str(inputs, i).as_bytes();
+        let mut j = 0;
+        while j < len && j < other.len() && first[first.len() - 1 - j] == other[other.len() - 1 - j]
+        {
+            j += 1;
+        }
+        len = j;
+        i += 1;
+    }
+    first.len() - next_char_boundary(get_str(inputs, 0), first.len() - len)
+}
+
+/// Returns the longest suffix shared by all elements of `array`.
+///
+/// Example usage:
+/// ```rust
+/// const EXT: &'static str = const_str_join::common_suffix_str!(["main.rs", "lib.rs"]);
+/// assert_eq!(EXT, ".rs");
+/// ```
+#[macro_export]
+macro_rules! common_suffix_str {
+    ($array:expr) => {
+        const {
+            const __ELEMS: &[&str] = &$array;
+            if __ELEMS.is_empty() {
+                ""
+            } else {
+                $crate::str_slice(
+                    $crate::get_str(__ELEMS, 0),
+                    $crate::get_str(__ELEMS, 0).len() - $crate::common_suffix_len(__ELEMS),
+                    $crate::get_str(__ELEMS, 0).len(),
+                )
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+pub const fn write_sanitized_filename(s: &str, output: &mut [u8]) -> usize {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        output[i] = match bytes[i] {
+            b'/' | b'\\' | b':' | b'*' | b'?' | b'"' | b'<' | b'>' | b'|' => b'_',
+            b if b.is_ascii_control() => b'_',
+            b => b,
+        };
+        i += 1;
+    }
+    i
+}
+
+/// Replaces every char of `s` that isn't allowed in file names on common
+/// file systems with `_`.
+///
+/// Those are `/ \ : * ? " < > |` as well as the ASCII control chars.
+/// Everything else, including non-ASCII chars, is kept, so the result
+/// has the same length as `s`. Reserved names such as `CON` on Windows
+/// aren't handled.
+///
+/// Example usage:
+/// ```rust
+/// const NAME: &'static str = const_str_join::sanitize_filename_str!("a/b:c*d");
+/// assert_eq!(NAME, "a_b_c_d");
+/// ```
+#[macro_export]
+macro_rules! sanitize_filename_str {
+    ($s:expr) => {
+        const {
+            const __INPUT: &str = $s;
+            $crate::__build_str!(__INPUT.len(), |buffer| {
+                $crate::write_sanitized_filename(__INPUT, &mut buffer)
+            })
+        }
+    };
+}
+
+#[doc(hidden)]
+pub const fn indexed_joined_size(inputs: &[&str], sep: &str, open: &str, close: &str) -> usize {
+    let mut n = required_size(inputs, Some(sep));
+    let mut i = 0;
+    while i < inputs.len() {
+        n += open.len() + decimal_len(i as u64) + close.len();
+        i += 1;
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_indexed_joined(
+    inputs: &[&str],
+    sep: &str,
+    open: &str,
+    close: &str,
+    output: &mut [u8],
+) -> usize {
+    let mut op = 0;
+    let mut i = 0;
+    while i < inputs.len() {
+        if i > 0 {
+            op = copy_bytes(sep.as_bytes(), output, op);
+        }
+        op = copy_bytes(get_str(inputs, i).as_bytes(), output, op);
+        op = copy_bytes(open.as_bytes(), output, op);
+        op = write_decimal(i as u64, output, op);
+        op = copy_bytes(close.as_bytes(), output, op);
+        i += 1;
+    }
+    op
+}
+
+/// Joins `array` with `sep`, writing the 0-based index of every element
+/// enclosed in `open` and `close` right after it.
+///
+/// Example usage:
+/// ```rust
+/// const S: &'static str = const_str_join::declare_indexed_join_str!(["a", "b", "c"], ",", "[", "]");
+/// assert_eq!(S, "a[0],b[1],c[2]");
+/// ```
+#[macro_export]
+macro_rules! declare_indexed_join_str {
+    ($array:expr, $sep:expr, $open:expr, $close:expr) => {
+        const {
+            const __SEP: &str = $crate::__separator!($sep);
+            const __OPEN: &str = $open;
+            const __CLOSE: &str = $close;
+            $crate::__build_str!(
+                $crate::indexed_joined_size(&$array, __SEP, __OPEN, __CLOSE),
+                |buffer| $crate::write_indexed_joined(&$array, __SEP, __OPEN, __CLOSE, &mut buffer)
+            )
+        }
+    };
+}
+
+/// Compares `a` and `b` byte-wise, the same order `str` implements
+/// [`Ord`] with.
+///
+/// ```rust
+/// use core::cmp::Ordering;
+/// use const_str_join::str_cmp;
+/// assert_eq!(str_cmp("abc", "abd"), Ordering::Less);
+/// assert_eq!(str_cmp("ab", "a"), Ordering::Greater);
+/// assert_eq!(str_cmp("a", "a"), Ordering::Equal);
+/// ```
+pub const fn str_cmp(a: &str, b: &str) -> core::cmp::Ordering {
+    let a = a.as_bytes();
+    let b = b.as_bytes();
+    let mut i = 0;
+    while i < a.len() && i < b.len() {
+        if a[i] != b[i] {
+            return if a[i] < b[i] {
+                core::cmp::Ordering::Less
+            } else {
+                core::cmp::Ordering::Greater
+            };
+        }
+        i += 1;
+    }
+    if a.len() < b.len() {
+        core::cmp::Ordering::Less
+    } else if a.len() > b.len() {
+        core::cmp::Ordering::Greater
+    } else {
+        core::cmp::Ordering::Equal
+    }
+}
+
+/// Returns the index of the first element of `inputs` that is smaller
+/// than the one before it, or `None` if `inputs` is sorted.
+#[doc(hidden)]
+pub const fn first_unsorted_index(inputs: &[&str]) -> Option<usize> {
+    let mut i = 1;
+    while i < inputs.len() {
+        if str_cmp(get_str(inputs, i - 1), get_str(inputs, i)).is_gt() {
+            return Some(i);
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Returns whether `inputs` is sorted in ascending order according to
+/// [`str_cmp`]. Equal neighbours are considered sorted.
+///
+/// ```rust
+/// const _: () = assert!(const_str_join::is_sorted(&["a", "b", "b", "c"]));
+/// const _: () = assert!(!const_str_join::is_sorted(&["b", "a"]));
+/// ```
+pub const fn is_sorted(inputs: &[&str]) -> bool {
+    first_unsorted_index(inputs).is_none()
+}
+
+/// Joins `array` with `sep` after asserting that it is sorted (see
+/// [`is_sorted`]).
+///
+/// If it isn't, compilation fails with the index of the first element
+/// that is smaller than the one before it.
+///
+/// Example usage:
+/// ```rust
+/// const S: &'static str = const_str_join::declare_joined_assert_sorted_str!(["alpha", "beta"], ",");
+/// assert_eq!(S, "alpha,beta");
+/// ```
+///
+/// ```compile_fail
+/// const S: &'static str = const_str_join::declare_joined_assert_sorted_str!(["beta", "alpha"], ",");
+/// ```
+#[macro_export]
+macro_rules! declare_joined_assert_sorted_str {
+    ($array:expr, $sep:expr) => {
+        const {
+            $crate::__assert_none_at!(
+                $crate::first_unsorted_index(&$array),
+                "array isn't sorted, out of order element at index "
+            );
+            $crate::const_join!($array, $sep)
+        }
+    };
+}
+
+/// Writes as many copies of `fill` as required for `src` to reach
+/// `width` chars followed by `src`. Strings that are already wider are
+/// written as-is.
+#[doc(hidden)]
+pub const fn pad_left_str(
+    src: &str,
+    width: usize,
+    fill: &str,
+    dest: &mut [u8],
+    mut offset: usize,
+) -> usize {
+    let mut n = char_count(src);
+    while n < width {
+        offset = copy_bytes(fill.as_bytes(), dest, offset);
+        n += 1;
+    }
+    copy_bytes(src.as_bytes(), dest, offset)
+}
+
+#[doc(hidden)]
+pub const fn rjust_joined_size(inputs: &[&str], sep: &str, fill: &str) -> usize {
+    let width = max_char_count(inputs);
+    let mut n = required_size(inputs, Some(sep));
+    let mut i = 0;
+    while i < inputs.len() {
+        n += (width - char_count(get_str(inputs, i))) * fill.len();
+        i += 1;
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_rjust_joined(
+    inputs: &[&str],
+    sep: &str,
+    fill: &str,
+    output: &mut [u8],
+) -> usize {
+    let width = max_char_count(inputs);
+    let mut op = 0;
+    let mut i = 0;
+    while i < inputs.len() {
+        if i > 0 {
+            op = copy_bytes(sep.as_bytes(), output, op);
+        }
+        op = pad_left_str(get_str(inputs, i), width, fill, output, op);
+        i += 1;
+    }
+    op
+}
+
+/// Right-aligns every element of `array` to the width (in chars) of the
+/// widest element by prepending `fill` (a `char`) and joins the result
+/// with `sep`.
+///
+/// Example usage:
+/// ```rust
+/// const COLUMN: &'static str = const_str_join::declare_rjust_join_str!(["1", "22", "333"], "\n", ' ');
+/// assert_eq!(COLUMN, "  1\n 22\n333");
+/// ```
+#[macro_export]
+macro_rules! declare_rjust_join_str {
+    ($array:expr, $sep:expr, $fill:expr) => {
+        const {
+            const __SEP: &str = $crate::__separator!($sep);
+            const __FILL: char = $fill;
+            const __FILL_STR: &str = $crate::__separator!(__FILL);
+            $crate::__build_str!(
+                $crate::rjust_joined_size(&$array, __SEP, __FILL_STR),
+                |buffer| { $crate::write_rjust_joined(&$array, __SEP, __FILL_STR, &mut buffer) }
+            )
+        }
+    };
+}
+
+/// Splits `s` around the first occurrence of `sep`, or returns `None` if
+/// `sep` doesn't occur in `s`.
+///
+/// Just like [`str::split_once`] an empty `sep` matches at the very
+/// start.
+///
+/// ```rust
+/// use const_str_join::split_once;
+/// assert_eq!(split_once("KEY=value", "="), Some(("KEY", "value")));
+/// assert_eq!(split_once("a=b=c", "="), Some(("a", "b=c")));
+/// assert_eq!(split_once("no separator", "="), None);
+/// ```
+pub const fn split_once<'a>(s: &'a str, sep: &str) -> Option<(&'a str, &'a str)> {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    while i + sep.len() <= bytes.len() {
+        if bytes_match_at(bytes, i, sep.as_bytes()) {
+            return Some((str_slice(s, 0, i), str_slice(s, i + sep.len(), s.len())));
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Splits `s` around the first occurrence of `sep` (a `&str` or a
+/// `char`) into a `(&'static str, &'static str)` pair.
+///
+/// Fails to compile if `sep` doesn't occur in `s`, use [`split_once`]
+/// directly to handle that case.
+///
+/// Example usage:
+/// ```rust
+/// const PAIR: (&'static str, &'static str) = const_str_join::split_once_str!("KEY=value", '=');
+/// assert_eq!(PAIR, ("KEY", "value"));
+/// ```
+///
+/// ```compile_fail
+/// const PAIR: (&'static str, &'static str) = const_str_join::split_once_str!("KEY", '=');
+/// ```
+#[macro_export]
+macro_rules! split_once_str {
+    ($s:expr, $sep:expr) => {
+        const {
+            if let Some(pair) = $crate::split_once($s, $crate::__separator!($sep)) {
+                pair
+            } else {
+                panic!("separator not found");
+            }
+        }
+    };
+}
+
+/// Returns the index of the first entry of `order` that is out of bounds
+/// for `len` elements or repeats an earlier entry, or `None` if `order`
+/// is a permutation of `0..len`.
+#[doc(hidden)]
+pub const fn first_invalid_permutation_index(order: &[usize], len: usize) -> Option<usize> {
+    assert!(
+        order.len() == len,
+        "order must have as many entries as the array"
+    );
+    let mut i = 0;
+    while i < order.len() {
+        if *get_elem(order, i) >= len {
+            return Some(i);
+        }
+        let mut j = 0;
+        while j < i {
+            if *get_elem(order, j) == *get_elem(order, i) {
+                return Some(i);
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    None
+}
+
+#[doc(hidden)]
+pub const fn write_permuted_joined(
+    inputs: &[&str],
+    sep: Option<&str>,
+    order: &[usize],
+    output: &mut [u8],
+) -> usize {
+    let mut op = 0;
+    let mut i = 0;
+    while i < order.len() {
+        if i > 0
+            && let Some(sep) = sep
+        {
+            op = copy_bytes(sep.as_bytes(), output, op);
+        }
+        op = copy_bytes(get_str(inputs, *get_elem(order, i)).as_bytes(), output, op);
+        i += 1;
+    }
+    op
+}
+
+/// Joins the elements of `array` with `sep` in the order given by
+/// `order`, the `i`-th element of the result being `array[order[i]]`.
+///
+/// `order` has to be a permutation: it must contain every index of
+/// `array` exactly once, otherwise compilation fails.
+///
+/// Example usage:
+/// ```rust
+/// const S: &'static str = const_str_join::declare_permuted_join_str!(["a", "b", "c"], ",", [2, 0, 1]);
+/// assert_eq!(S, "c,a,b");
+/// ```
+///
+/// ```compile_fail
+/// const S: &'static str = const_str_join::declare_permuted_join_str!(["a", "b", "c"], ",", [0, 0, 1]);
+/// ```
+#[macro_export]
+macro_rules! declare_permuted_join_str {
+    ($array:expr, $sep:expr, $order:expr) => {
+        const {
+            const __ELEMS: &[&str] = &$array;
+            const __ORDER: &[usize] = &$order;
+            $crate::__assert_none_at!(
+                $crate::first_invalid_permutation_index(__ORDER, __ELEMS.len()),
+                "order isn't a permutation, invalid entry at index "
+            );
+            const __SEP: Option<&str> = $crate::non_empty_sep($crate::__separator!($sep));
+            $crate::__build_str!($crate::required_size(__ELEMS, __SEP), |buffer| {
+                $crate::write_permuted_joined(__ELEMS, __SEP, __ORDER, &mut buffer)
+            })
+        }
+    };
+}
+
+/// Declares two constants: `$name`, the joined string of `array` and
+/// `sep`, and `$len`, its length in bytes.
+///
+/// Both get the visibility given before `$name`.
+///
+/// Example usage:
+/// ```rust
+/// const_str_join::declare_joined_str_with_len!(pub HEADER, HEADER_LEN, ["MAGIC", "v1"], ':');
+/// assert_eq!(HEADER, "MAGIC:v1");
+/// assert_eq!(HEADER_LEN, 8);
+/// let _fixed: [u8; HEADER_LEN] = *b"MAGIC:v1";
+/// ```
+#[macro_export]
+macro_rules! declare_joined_str_with_len {
+    ($vis:vis $name:ident, $len:ident, $array:expr, $sep:expr) => {
+        $vis const $name: &str = $crate::const_join!($array, $sep);
+        $vis const $len: usize = $name.len();
+    };
+}
+
+/// Declares a `static` named `$bytes` holding the joined bytes of
+/// `array` and `sep` as `[u8; N]`, and a `static` named `$name` with the
+/// same bytes as `&str`.
+///
+/// Every expansion of [`const_join!`] (and the other `&'static str`
+/// macros) gets its own anonymous backing `static` with the joined
+/// bytes, two expansions with the same input end up as two copies in
+/// the binary unless the linker happens to merge them. Declaring the
+/// storage once with this macro and referring to `$name` or `$bytes`
+/// everywhere else guarantees a single copy.
+///
+/// Example usage:
+/// ```rust
+/// const_str_join::declare_joined_static!(pub USER_AGENT, USER_AGENT_BYTES, ["my-crate", "1.0"], '/');
+///
+/// fn user_agent() -> &'static str {
+///     USER_AGENT
+/// }
+/// assert_eq!(user_agent(), "my-crate/1.0");
+/// assert_eq!(&USER_AGENT_BYTES, b"my-crate/1.0");
+/// ```
+#[macro_export]
+macro_rules! declare_joined_static {
+    ($vis:vis $name:ident, $bytes:ident, $array:expr, $sep:expr) => {
+        $vis static $bytes: [u8; $crate::required_size(
+            &$array,
+            $crate::non_empty_sep($crate::__separator!($sep)),
+        )] = $crate::joined_array!($array, $sep);
+        $vis static $name: &str = if let Ok(v) = core::str::from_utf8(&$bytes) {
+            v
+        } else {
+            panic!("joined array isn't a valid utf8 string");
+        };
+    };
+}
+
+const fn max_key_char_count(pairs: &[(&str, &str)]) -> usize {
+    let mut max = 0;
+    let mut i = 0;
+    while i < pairs.len() {
+        let n = char_count(get_elem(pairs, i).0);
+        if n > max {
+            max = n;
+        }
+        i += 1;
+    }
+    max
+}
+
+#[doc(hidden)]
+pub const fn aligned_pairs_size(pairs: &[(&str, &str)], kv_sep: &str, sep: &str) -> usize {
+    let width = max_key_char_count(pairs);
+    let mut n = pairs_size(pairs, kv_sep, sep);
+    let mut i = 0;
+    while i < pairs.len() {
+        n += width - char_count(get_elem(pairs, i).0);
+        i += 1;
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_aligned_pairs(
+    pairs: &[(&str, &str)],
+    kv_sep: &str,
+    sep: &str,
+    output: &mut [u8],
+) -> usize {
+    let width = max_key_char_count(pairs);
+    let mut op = 0;
+    let mut i = 0;
+    while i < pairs.len() {
+        if i > 0 {
+            op = copy_bytes(sep.as_bytes(), output, op);
+        }
+        op = pad_right_str(get_elem(pairs, i).0, width, b' ', output, op);
+        op = copy_bytes(kv_sep.as_bytes(), output, op);
+        op = copy_bytes(get_elem(pairs, i).1.as_bytes(), output, op);
+        i += 1;
+    }
+    op
+}
+
+/// Joins `(key, value)` pairs as `key` `kv_sep` `value` with `sep`,
+/// padding the keys with spaces so that all `kv_sep` line up.
+///
+/// Every key is padded to the width (in chars) of the widest key, so
+/// `kv_sep` starts at that column on every line provided `sep` is a
+/// line break.
+///
+/// Example usage:
+/// ```rust
+/// const DOC: &'static str = const_str_join::declare_aligned_kv_str!([("short", "1"), ("longer", "2")], " = ", "\n");
+/// assert_eq!(DOC, "short  = 1\nlonger = 2");
+/// ```
+#[macro_export]
+macro_rules! declare_aligned_kv_str {
+    ($pairs:expr, $kv_sep:expr, $sep:expr) => {
+        const {
+            const __KV_SEP: &str = $crate::__separator!($kv_sep);
+            const __SEP: &str = $crate::__separator!($sep);
+            $crate::__build_str!(
+                $crate::aligned_pairs_size(&$pairs, __KV_SEP, __SEP),
+                |buffer| { $crate::write_aligned_pairs(&$pairs, __KV_SEP, __SEP, &mut buffer) }
+            )
+        }
+    };
+}
+
+#[doc(hidden)]
+pub const fn write_pascal_case(inputs: &[&str], output: &mut [u8]) -> usize {
+    let mut op = 0;
+    let mut i = 0;
+    while i < inputs.len() {
+        let start = op;
+        op = copy_bytes(get_str(inputs, i).as_bytes(), output, op);
+        if op > start {
+            output[start] = output[start].to_ascii_uppercase();
+        }
+        i += 1;
+    }
+    op
+}
+
+/// Concatenates `array` with the first byte of every element uppercased
+/// if it is an ASCII letter.
+///
+/// The rest of every element is left as-is, so all-caps elements stay
+/// all-caps.
+///
+/// Example usage:
+/// ```rust
+/// const NAME: &'static str = const_str_join::pascal_case_str!(["my", "http", "server"]);
+/// assert_eq!(NAME, "MyHttpServer");
+/// ```
+#[macro_export]
+macro_rules! pascal_case_str {
+    ($array:expr) => {
+        $crate::__build_str!($crate::required_size(&$array, None), |buffer| {
+            $crate::write_pascal_case(&$array, &mut buffer)
+        })
+    };
+}
+
+/// A string of at most `CAP` bytes stored inline, as returned by
+/// [`declare_inline_str!`].
+///
+/// Unlike the `&'static str` macros no separate static is needed, the
+/// value can be stored and copied like any other array. The unused tail
+/// of the buffer isn't part of the string.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct InlineStr<const CAP: usize> {
+    bytes: [u8; CAP],
+    len: usize,
+}
+
+impl<const CAP: usize> InlineStr<CAP> {
+    /// Copies `s` into a new inline string, panics if it is longer than
+    /// `CAP` bytes.
+    pub const fn new(s: &str) -> Self {
+        assert!(s.len() <= CAP, "string exceeds the inline capacity");
+        let mut bytes = [0u8; CAP];
+        let len = copy_bytes(s.as_bytes(), &mut bytes, 0);
+        InlineStr { bytes, len }
+    }
+
+    #[doc(hidden)]
+    pub const fn from_joined(inputs: &[&str], sep: Option<&str>) -> Self {
+        assert!(
+            required_size(inputs, sep) <= CAP,
+            "joined string exceeds the inline capacity"
+        );
+        let mut bytes = [0u8; CAP];
+        let len = write_joined(inputs, sep, &mut bytes, 0);
+        InlineStr { bytes, len }
+    }
+
+    /// Returns the length of the string in bytes.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the string is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the bytes of the string, without the unused tail.
+    pub const fn as_bytes(&self) -> &[u8] {
+        self.bytes.split_at(self.len).0
+    }
+
+    /// Returns the string.
+    pub const fn as_str(&self) -> &str {
+        if let Ok(v) = core::str::from_utf8(self.as_bytes()) {
+            v
+        } else {
+            panic!("inline string isn't a valid utf8 string");
+        }
+    }
+}
+
+impl<const CAP: usize> core::fmt::Debug for InlineStr<CAP> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+/// Returns the joined string of `array` and `sep` as an [`InlineStr`]
+/// with a capacity of `cap` bytes.
+///
+/// The joined string may be shorter than `cap`, longer ones fail to
+/// compile.
+///
+/// Example usage:
+/// ```rust
+/// use const_str_join::{declare_inline_str, InlineStr};
+///
+/// const NAME: InlineStr<16> = declare_inline_str!(["dev", "eth0"], '/', 16);
+/// assert_eq!(NAME.as_str(), "dev/eth0");
+/// assert_eq!(NAME.len(), 8);
+/// ```
+///
+/// ```compile_fail
+/// let name = const_str_join::declare_inline_str!(["dev", "eth0"], '/', 4);
+/// ```
+#[macro_export]
+macro_rules! declare_inline_str {
+    ($array:expr, $sep:expr, $cap:expr) => {
+        const {
+            const __SEP: Option<&str> = $crate::non_empty_sep($crate::__separator!($sep));
+            $crate::InlineStr::<{ $cap }>::from_joined(&$array, __SEP)
+        }
+    };
+}
+
+/// Returns the 32-bit FNV-1a hash of `bytes`.
+///
+/// ```rust
+/// use const_str_join::fnv1a;
+/// assert_eq!(fnv1a(b""), 0x811c9dc5);
+/// assert_eq!(fnv1a(b"a"), 0xe40c292c);
+/// ```
+pub const fn fnv1a(bytes: &[u8]) -> u32 {
+    let mut hash: u32 = 0x811c9dc5;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u32;
+        hash = hash.wrapping_mul(0x01000193);
+        i += 1;
+    }
+    hash
+}
+
+/// Writes `n` as 8 lowercase, zero-padded hex digits at `offset`.
+#[doc(hidden)]
+pub const fn write_hex_u32(n: u32, dest: &mut [u8], offset: usize) -> usize {
+    let mut i = 0;
+    while i < 8 {
+        let digit = ((n >> (28 - 4 * i)) & 0xf) as u8;
+        dest[offset + i] = if digit < 10 {
+            b'0' + digit
+        } else {
+            b'a' + digit - 10
+        };
+        i += 1;
+    }
+    offset + 8
+}
+
+/// Joins `array` with `sep` and appends `hash_sep` (`#` if omitted)
+/// followed by the [`fnv1a`] hash of the joined string as 8 hex digits.
+///
+/// Example usage:
+/// ```rust
+/// const KEY: &'static str = const_str_join::declare_joined_with_hash_str!(["a", "b", "c"], ",");
+/// assert_eq!(KEY, "a,b,c#7a8f5e87");
+/// const TAGGED: &'static str = const_str_join::declare_joined_with_hash_str!(["a"], ",", '@');
+/// assert_eq!(TAGGED, "a@e40c292c");
+/// ```
+#[macro_export]
+macro_rules! declare_joined_with_hash_str {
+    ($array:expr, $sep:expr) => {
+        $crate::declare_joined_with_hash_str!($array, $sep, "#")
+    };
+    ($array:expr, $sep:expr, $hash_sep:expr) => {
+        const {
+            const __JOINED: &str = $crate::const_join!($array, $sep);
+            const __HASH_SEP: &str = $crate::__separator!($hash_sep);
+            const __HASH: u32 = $crate::fnv1a(__JOINED.as_bytes());
+            $crate::__build_str!(__JOINED.len() + __HASH_SEP.len() + 8, |buffer| {
+                let op = $crate::copy_bytes(__JOINED.as_bytes(), &mut buffer, 0);
+                let op = $crate::copy_bytes(__HASH_SEP.as_bytes(), &mut buffer, op);
+                $crate::write_hex_u32(__HASH, &mut buffer, op)
+            })
+        }
+    };
+}
+
+const fn hex_digit(b: u8) -> Option<u8> {
+    match b {
+        b'0'..=b'9' => Some(b - b'0'),
+        b'a'..=b'f' => Some(b - b'a' + 10),
+        b'A'..=b'F' => Some(b - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Decodes the escape sequence starting with the backslash at `bytes[i]`
+/// into `(length of the sequence, decoded byte)`, or `None` if it isn't
+/// a supported one.
+const fn decode_escape(bytes: &[u8], i: usize) -> Option<(usize, u8)> {
+    if i + 1 >= bytes.len() {
+        return None;
+    }
+    match bytes[i + 1] {
+        b'n' => Some((2, b'\n')),
+        b't' => Some((2, b'\t')),
+        b'r' => Some((2, b'\r')),
+        b'0' => Some((2, b'\0')),
+        b'\\' => Some((2, b'\\')),
+        b'"' => Some((2, b'"')),
+        b'\'' => Some((2, b'\'')),
+        b'x' if i + 3 < bytes.len() => match (hex_digit(bytes[i + 2]), hex_digit(bytes[i + 3])) {
+            (Some(high), Some(low)) if high < 8 => Some((4, high << 4 | low)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns the index of the first backslash in `s` that doesn't start a
+/// supported escape sequence, or `None` if all of them do.
+#[doc(hidden)]
+pub const fn first_invalid_escape_index(s: &str) -> Option<usize> {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' {
+            match decode_escape(bytes, i) {
+                Some((len, _)) => i += len,
+                None => return Some(i),
+            }
+        } else {
+            i += 1;
+        }
+    }
+    None
+}
+
+const fn unescape(s: &str, mut output: Option<&mut [u8]>) -> usize {
+    let bytes = s.as_bytes();
+    let mut op = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        // invalid escapes are reported by `first_invalid_escape_index`,
+        // keep them as-is instead of failing without an index
+        let (len, b) = if bytes[i] == b'\\'
+            && let Some(decoded) = decode_escape(bytes, i)
+        {
+            decoded
+        } else {
+            (1, bytes[i])
+        };
+        if let Some(output) = &mut output {
+            output[op] = b;
+        }
+        op += 1;
+        i += len;
+    }
+    op
+}
+
+#[doc(hidden)]
+pub const fn unescaped_size(s: &str) -> usize {
+    unescape(s, None)
+}
+
+#[doc(hidden)]
+pub const fn write_unescaped(s: &str, output: &mut [u8]) -> usize {
+    unescape(s, Some(output))
+}
+
+/// Replaces the backslash escape sequences in `s` with the chars they
+/// stand for.
+///
+/// Supported are `\n`, `\t`, `\r`, `\0`, `\\`, `\"`, `\'` and, just like
+/// in Rust literals, `\xHH` for ASCII chars up to `\x7f`. Any other
+/// backslash fails compilation with its index.
+///
+/// Example usage:
+/// ```rust
+/// const S: &'static str = const_str_join::unescape_str!(r#"a\nb\t\"c\"\x41"#);
+/// assert_eq!(S, "a\nb\t\"c\"A");
+/// ```
+///
+/// ```compile_fail
+/// const S: &'static str = const_str_join::unescape_str!(r"unknown \q");
+/// ```
+#[macro_export]
+macro_rules! unescape_str {
+    ($s:expr) => {
+        const {
+            const __INPUT: &str = $s;
+            $crate::__assert_none_at!(
+                $crate::first_invalid_escape_index(__INPUT),
+                "invalid escape sequence at index "
+            );
+            $crate::__build_str!($crate::unescaped_size(__INPUT), |buffer| {
+                $crate::write_unescaped(__INPUT, &mut buffer)
+            })
+        }
+    };
+}
+
+/// Returns the number of lines in `s`, that is the number of `\n` plus
+/// one.
+///
+/// An empty string has no lines at all, while a trailing newline starts
+/// a last, empty line.
+///
+/// ```rust
+/// use const_str_join::line_count;
+/// assert_eq!(line_count(""), 0);
+/// assert_eq!(line_count("one"), 1);
+/// assert_eq!(line_count("one\ntwo"), 2);
+/// assert_eq!(line_count("one\n"), 2);
+/// ```
+pub const fn line_count(s: &str) -> usize {
+    if s.is_empty() {
+        return 0;
+    }
+    let bytes = s.as_bytes();
+    let mut n = 1;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\n' {
+            n += 1;
+        }
+        i += 1;
+    }
+    n
+}
+
+/// Joins `array` with `sep` and fails compilation unless the result has
+/// exactly `expected` lines as counted by [`line_count`].
+///
+/// Example usage:
+/// ```rust
+/// const TEXT: &'static str = const_str_join::declare_joined_str_assert_lines!(["a", "b\nc"], "\n", 3);
+/// assert_eq!(TEXT, "a\nb\nc");
+/// ```
+///
+/// ```compile_fail
+/// const TEXT: &'static str = const_str_join::declare_joined_str_assert_lines!(["a", "b"], "\n", 3);
+/// ```
+#[macro_export]
+macro_rules! declare_joined_str_assert_lines {
+    ($array:expr, $sep:expr, $expected:expr) => {
+        const {
+            const __JOINED: &str = $crate::const_join!($array, $sep);
+            const __LINES: usize = $crate::line_count(__JOINED);
+            const __EXPECTED: usize = $expected;
+            if __LINES != __EXPECTED {
+                panic!(
+                    "{}",
+                    $crate::build_str!(
+                        "joined string has ",
+                        $crate::__usize_str!(__LINES),
+                        " lines, expected ",
+                        $crate::__usize_str!(__EXPECTED)
+                    )
+                );
+            }
+            __JOINED
+        }
+    };
+}
+
+#[doc(hidden)]
+pub const fn offset_annotated_size(inputs: &[&str], sep: &str) -> usize {
+    let mut n = required_size(inputs, Some(sep));
+    let mut offset = 0;
+    let mut i = 0;
+    while i < inputs.len() {
+        n += decimal_len(offset as u64) + 1;
+        offset += get_str(inputs, i).len();
+        i += 1;
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_offset_annotated(inputs: &[&str], sep: &str, output: &mut [u8]) -> usize {
+    let mut op = 0;
+    let mut offset = 0;
+    let mut i = 0;
+    while i < inputs.len() {
+        if i > 0 {
+            op = copy_bytes(sep.as_bytes(), output, op);
+        }
+        op = write_decimal(offset as u64, output, op);
+        op = copy_bytes(b":", output, op);
+        op = copy_bytes(get_str(inputs, i).as_bytes(), output, op);
+        offset += get_str(inputs, i).len();
+        i += 1;
+    }
+    op
+}
+
+/// Joins `array` with `sep`, prefixing every element with its offset
+/// and a `:`.
+///
+/// The offset is the number of bytes of all elements before it, neither
+/// the separators nor the annotations are counted. It is therefore the
+/// offset of the element in the plain concatenation of `array`.
+///
+/// Example usage:
+/// ```rust
+/// const S: &'static str = const_str_join::declare_offset_annotated_str!(["ab", "cde", "f"], "\n");
+/// assert_eq!(S, "0:ab\n2:cde\n5:f");
+/// ```
+#[macro_export]
+macro_rules! declare_offset_annotated_str {
+    ($array:expr, $sep:expr) => {
+        const {
+            const __SEP: &str = $crate::__separator!($sep);
+            $crate::__build_str!($crate::offset_annotated_size(&$array, __SEP), |buffer| {
+                $crate::write_offset_annotated(&$array, __SEP, &mut buffer)
+            })
+        }
+    };
+}
+
+const fn rust_escaped_len(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let mut n = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        n += match bytes[i] {
+            b'"' | b'\\' | b'\n' | b'\r' | b'\t' => 2,
+            b if b.is_ascii_control() => 4,
+            _ => 1,
+        };
+        i += 1;
+    }
+    n
+}
+
+/// Writes `s` escaped for a Rust string literal at `offset`.
+#[doc(hidden)]
+pub const fn write_rust_escaped(s: &str, output: &mut [u8], offset: usize) -> usize {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    let bytes = s.as_bytes();
+    let mut op = offset;
+    let mut i = 0;
+    while i < bytes.len() {
+        op = match bytes[i] {
+            b'"' => copy_bytes(b"\\\"", output, op),
+            b'\\' => copy_bytes(b"\\\\", output, op),
+            b'\n' => copy_bytes(b"\\n", output, op),
+            b'\r' => copy_bytes(b"\\r", output, op),
+            b'\t' => copy_bytes(b"\\t", output, op),
+            b if b.is_ascii_control() => {
+                let hex = [b'\\', b'x', HEX[(b >> 4) as usize], HEX[(b & 0xf) as usize]];
+                copy_bytes(&hex, output, op)
+            }
+            b => copy_bytes(&[b], output, op),
+        };
+        i += 1;
+    }
+    op
+}
+
+#[doc(hidden)]
+pub const fn rust_array_literal_size(inputs: &[&str]) -> usize {
+    // brackets, quotes and ", " separators
+    let mut n = if inputs.is_empty() {
+        2
+    } else {
+        4 * inputs.len()
+    };
+    let mut i = 0;
+    while i < inputs.len() {
+        n += rust_escaped_len(get_str(inputs, i));
+        i += 1;
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_rust_array_literal(inputs: &[&str], output: &mut [u8]) -> usize {
+    let mut op = copy_bytes(b"[", output, 0);
+    let mut i = 0;
+    while i < inputs.len() {
+        if i > 0 {
+            op = copy_bytes(b", ", output, op);
+        }
+        op = copy_bytes(b"\"", output, op);
+        op = write_rust_escaped(get_str(inputs, i), output, op);
+        op = copy_bytes(b"\"", output, op);
+        i += 1;
+    }
+    copy_bytes(b"]", output, op)
+}
+
+/// Renders `array` as the source of a Rust array literal of string
+/// literals, e.g. for writing generated `.rs` files.
+///
+/// Quotes, backslashes and ASCII control chars are escaped, everything
+/// else is kept as-is.
+///
+/// Example usage:
+/// ```rust
+/// const SRC: &'static str = const_str_join::declare_rust_array_literal_str!(["a", "say \"hi\""]);
+/// assert_eq!(SRC, r#"["a", "say \"hi\""]"#);
+/// ```
+#[macro_export]
+macro_rules! declare_rust_array_literal_str {
+    ($array:expr) => {
+        $crate::__build_str!($crate::rust_array_literal_size(&$array), |buffer| {
+            $crate::write_rust_array_literal(&$array, &mut buffer)
+        })
+    };
+}
+
+const fn ends_with(s: &str, suffix: &str) -> bool {
+    s.len() >= suffix.len()
+        && bytes_match_at(s.as_bytes(), s.len() - suffix.len(), suffix.as_bytes())
+}
+
+#[doc(hidden)]
+pub const fn smart_joined_size(inputs: &[&str], sep: &str) -> usize {
+    let mut n = 0;
+    let mut i = 0;
+    while i < inputs.len() {
+        if i > 0 && !ends_with(get_str(inputs, i - 1), sep) {
+            n += sep.len();
+        }
+        n += get_str(inputs, i).len();
+        i += 1;
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_smart_joined(inputs: &[&str], sep: &str, output: &mut [u8]) -> usize {
+    let mut op = 0;
+    let mut i = 0;
+    while i < inputs.len() {
+        if i > 0 && !ends_with(get_str(inputs, i - 1), sep) {
+            op = copy_bytes(sep.as_bytes(), output, op);
+        }
+        op = copy_bytes(get_str(inputs, i).as_bytes(), output, op);
+        i += 1;
+    }
+    op
+}
+
+/// Joins `array` with `sep`, skipping the separator after elements that
+/// already end with it.
+///
+/// Only the end of the previous element is checked, a separator at the
+/// start of the next element is still doubled.
+///
+/// Example usage:
+/// ```rust
+/// const TEXT: &'static str = const_str_join::declare_joined_smart_str!(["one\n", "two", "three"], "\n");
+/// assert_eq!(TEXT, "one\ntwo\nthree");
+/// ```
+#[macro_export]
+macro_rules! declare_joined_smart_str {
+    ($array:expr, $sep:expr) => {
+        const {
+            const __SEP: &str = $crate::__separator!($sep);
+            $crate::__build_str!($crate::smart_joined_size(&$array, __SEP), |buffer| {
+                $crate::write_smart_joined(&$array, __SEP, &mut buffer)
+            })
+        }
+    };
+}
+
+/// Panics with a message naming `index` and `len`, the const
+/// counterpart of the message of a failed slice index.
+#[doc(hidden)]
+pub const fn index_out_of_bounds(index: usize, len: usize) -> ! {
+    let mut buffer = [0u8; 80];
+    let mut op = copy_bytes(b"index ", &mut buffer, 0);
+    op = write_decimal(index as u64, &mut buffer, op);
+    op = copy_bytes(b" out of bounds for length ", &mut buffer, op);
+    op = write_decimal(len as u64, &mut buffer, op);
+    if let Ok(msg) = core::str::from_utf8(buffer.split_at(op).0) {
+        panic!("{}", msg)
+    } else {
+        panic!("index out of bounds")
+    }
+}
+
+/// Returns `arr[i]`, panicking with a message naming `i` and the length
+/// of `arr` if it is out of bounds.
+///
+/// The message is shown as the compile error when used in a const,
+/// unlike the generic one of a plain `arr[i]`. All the macros of this
+/// crate index their inputs through it.
+///
+/// ```rust
+/// assert_eq!(const_str_join::get_str(&["a", "b"], 1), "b");
+/// ```
+///
+/// ```compile_fail
+/// // error: index 2 out of bounds for length 2
+/// const S: &str = const_str_join::get_str(&["a", "b"], 2);
+/// ```
+pub const fn get_str<'a>(arr: &[&'a str], i: usize) -> &'a str {
+    get_elem::<&str>(arr, i)
+}
+
+/// Returns `bytes[i]`, panicking with a message naming `i` and the
+/// length of `bytes` if it is out of bounds.
+///
+/// ```rust
+/// assert_eq!(const_str_join::get_byte(b"ab", 0), b'a');
+/// ```
+pub const fn get_byte(bytes: &[u8], i: usize) -> u8 {
+    *get_elem(bytes, i)
+}
+
+/// The generic version of [`get_str`] for the other element types the
+/// macros take, like pairs and numbers.
+#[doc(hidden)]
+pub const fn get_elem<T>(arr: &[T], i: usize) -> &T {
+    if i >= arr.len() {
+        index_out_of_bounds(i, arr.len());
+    }
+    &arr[i]
+}
+
+const fn zero_padded_len(n: usize, width: usize) -> usize {
+    let len = decimal_len(n as u64);
+    if len < width { width } else { len }
+}
+
+#[doc(hidden)]
+pub const fn zeropad_joined_size(numbers: &[usize], sep: &str, width: usize) -> usize {
+    let mut n = 0;
+    let mut i = 0;
+    while i < numbers.len() {
+        if i > 0 {
+            n += sep.len();
+        }
+        n += zero_padded_len(*get_elem(numbers, i), width);
+        i += 1;
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_zeropad_joined(
+    numbers: &[usize],
+    sep: &str,
+    width: usize,
+    output: &mut [u8],
+) -> usize {
+    let mut op = 0;
+    let mut i = 0;
+    while i < numbers.len() {
+        if i > 0 {
+            op = copy_bytes(sep.as_bytes(), output, op);
+        }
+        let mut zeros = zero_padded_len(*get_elem(numbers, i), width)
+            - decimal_len(*get_elem(numbers, i) as u64);
+        while zeros > 0 {
+            output[op] = b'0';
+            op += 1;
+            zeros -= 1;
+        }
+        op = write_decimal(*get_elem(numbers, i) as u64, output, op);
+        i += 1;
+    }
+    op
+}
+
+/// Joins the decimal representations of an array of `usize` with `sep`,
+/// padding every number with leading zeros to `width` digits.
+///
+/// Numbers with more than `width` digits are written as-is.
+///
+/// Example usage:
+/// ```rust
+/// const IDS: &'static str = const_str_join::declare_joined_zeropad_str!([1, 22, 333], ",", 4);
+/// assert_eq!(IDS, "0001,0022,0333");
+/// ```
+#[macro_export]
+macro_rules! declare_joined_zeropad_str {
+    ($array:expr, $sep:expr, $width:expr) => {
+        const {
+            const __NUMBERS: &[usize] = &$array;
+            const __SEP: &str = $crate::__separator!($sep);
+            const __WIDTH: usize = $width;
+            $crate::__build_str!(
+                $crate::zeropad_joined_size(__NUMBERS, __SEP, __WIDTH),
+                |buffer| $crate::write_zeropad_joined(__NUMBERS, __SEP, __WIDTH, &mut buffer)
+            )
+        }
+    };
+}
+
+/// Returns the index of the first element of `inputs` that contains
+/// `sep`, or `None` if joining them with `sep` can be split apart again
+/// unambiguously.
+///
+/// An empty `sep` never collides and always yields `None`.
+///
+/// ```rust
+/// use const_str_join::first_collision;
+/// assert_eq!(first_collision(&["a", "b,c", "d"], ","), Some(1));
+/// assert_eq!(first_collision(&["a", "b"], ","), None);
+/// ```
+pub const fn first_collision(inputs: &[&str], sep: &str) -> Option<usize> {
+    if sep.is_empty() {
+        return None;
+    }
+    let mut i = 0;
+    while i < inputs.len() {
+        let bytes = get_str(inputs, i).as_bytes();
+        let mut j = 0;
+        while j + sep.len() <= bytes.len() {
+            if bytes_match_at(bytes, j, sep.as_bytes()) {
+                return Some(i);
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Joins `array` with `sep` after asserting that no element contains
+/// `sep` (see [`first_collision`]), so the result can be split apart
+/// again.
+///
+/// Example usage:
+/// ```rust
+/// const S: &'static str = const_str_join::declare_joined_checked_str!(["a", "b"], ',');
+/// assert_eq!(S, "a,b");
+/// ```
+///
+/// ```compile_fail
+/// const S: &'static str = const_str_join::declare_joined_checked_str!(["a", "b,c"], ',');
+/// ```
+#[macro_export]
+macro_rules! declare_joined_checked_str {
+    ($array:expr, $sep:expr) => {
+        const {
+            $crate::__assert_none_at!(
+                $crate::first_collision(&$array, $crate::__separator!($sep)),
+                "element contains the separator, at index "
+            );
+            $crate::const_join!($array, $sep)
+        }
+    };
+}
+
+#[doc(hidden)]
+pub const fn tight_joined_size(inputs: &[&str], sep: &str) -> usize {
+    let mut n = 0;
+    let mut i = 0;
+    while i < inputs.len() {
+        if !get_str(inputs, i).is_empty() {
+            if n > 0 {
+                n += sep.len();
+            }
+            n += get_str(inputs, i).len();
+        }
+        i += 1;
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_tight_joined(inputs: &[&str], sep: &str, output: &mut [u8]) -> usize {
+    let mut op = 0;
+    let mut i = 0;
+    while i < inputs.len() {
+        if !get_str(inputs, i).is_empty() {
+            if op > 0 {
+                op = copy_bytes(sep.as_bytes(), output, op);
+            }
+            op = copy_bytes(get_str(inputs, i).as_bytes(), output, op);
+        }
+        i += 1;
+    }
+    op
+}
+
+/// Joins `array` with `sep`, writing separators only between non-empty
+/// elements.
+///
+/// Empty elements contribute nothing, not even a separator, no matter
+/// whether they are at the start, the end or in between.
+///
+/// Example usage:
+/// ```rust
+/// const S: &'static str = const_str_join::declare_joined_tight_str!(["", "a", "", "b", ""], "-");
+/// assert_eq!(S, "a-b");
+/// ```
+#[macro_export]
+macro_rules! declare_joined_tight_str {
+    ($array:expr, $sep:expr) => {
+        const {
+            const __SEP: &str = $crate::__separator!($sep);
+            $crate::__build_str!($crate::tight_joined_size(&$array, __SEP), |buffer| {
+                $crate::write_tight_joined(&$array, __SEP, &mut buffer)
+            })
+        }
+    };
+}
+
+/// Builds a C preprocessor `#define` line for `name`.
+///
+/// The value is either a single `&str` or an array of parts joined with
+/// `sep`. No newline is appended.
+///
+/// Example usage:
+/// ```rust
+/// use const_str_join::declare_c_define_str;
+///
+/// const MAX: &'static str = declare_c_define_str!("MAX_LEN", "256");
+/// assert_eq!(MAX, "#define MAX_LEN 256");
+/// const RGB: &'static str = declare_c_define_str!("RGB", ["255", "128", "0"], ", ");
+/// assert_eq!(RGB, "#define RGB 255, 128, 0");
+/// ```
+#[macro_export]
+macro_rules! declare_c_define_str {
+    ($name:expr, $value:expr) => {
+        $crate::build_str!("#define ", $name, " ", $value)
+    };
+    ($name:expr, $values:expr, $sep:expr) => {
+        $crate::build_str!("#define ", $name, " ", join($values, $sep))
+    };
+}
+
+/// Returns the index of the element of `inputs` with the most chars, or
+/// `None` if `inputs` is empty.
+///
+/// On ties the first of the widest elements wins.
+///
+/// ```rust
+/// use const_str_join::widest_index;
+/// assert_eq!(widest_index(&["a", "ccc", "bb"]), Some(1));
+/// assert_eq!(widest_index(&[]), None);
+/// ```
+pub const fn widest_index(inputs: &[&str]) -> Option<usize> {
+    if inputs.is_empty() {
+        return None;
+    }
+    let mut widest = 0;
+    let mut width = char_count(get_str(inputs, 0));
+    let mut i = 1;
+    while i < inputs.len() {
+        let n = char_count(get_str(inputs, i));
+        if n > width {
+            widest = i;
+            width = n;
+        }
+        i += 1;
+    }
+    Some(widest)
+}
+
+const fn is_url_unreserved(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~')
+}
+
+#[doc(hidden)]
+pub const fn url_path_size(segments: &[&str]) -> usize {
+    let mut n = if segments.is_empty() {
+        0
+    } else {
+        segments.len() - 1
+    };
+    let mut i = 0;
+    while i < segments.len() {
+        let bytes = get_str(segments, i).as_bytes();
+        let mut j = 0;
+        while j < bytes.len() {
+            n += if is_url_unreserved(bytes[j]) { 1 } else { 3 };
+            j += 1;
+        }
+        i += 1;
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_url_path(segments: &[&str], output: &mut [u8]) -> usize {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+    let mut op = 0;
+    let mut i = 0;
+    while i < segments.len() {
+        if i > 0 {
+            op = copy_bytes(b"/", output, op);
+        }
+        let bytes = get_str(segments, i).as_bytes();
+        let mut j = 0;
+        while j < bytes.len() {
+            let b = bytes[j];
+            op = if is_url_unreserved(b) {
+                copy_bytes(&[b], output, op)
+            } else {
+                copy_bytes(
+                    &[b'%', HEX[(b >> 4) as usize], HEX[(b & 0xf) as usize]],
+                    output,
+                    op,
+                )
+            };
+            j += 1;
+        }
+        i += 1;
+    }
+    op
+}
+
+/// Joins `segments` with `/` into a URL path, percent-encoding every
+/// segment.
+///
+/// Everything but the unreserved chars of RFC 3986 (ASCII letters and
+/// digits as well as `-`, `.`, `_` and `~`) is encoded, including any
+/// `/` within a segment. Non-ASCII chars are encoded byte by byte in
+/// their UTF-8 form.
+///
+/// Example usage:
+/// ```rust
+/// const PATH: &'static str = const_str_join::declare_url_path_str!(["a b", "c/d"]);
+/// assert_eq!(PATH, "a%20b/c%2Fd");
+/// ```
+#[macro_export]
+macro_rules! declare_url_path_str {
+    ($segments:expr) => {
+        $crate::__build_str!($crate::url_path_size(&$segments), |buffer| {
+            $crate::write_url_path(&$segments, &mut buffer)
+        })
+    };
+}
+
+/// Returns an HTTP ETag header value derived from the [`fnv1a`] hash of
+/// `array` joined with `sep`, e.g. `"7a8f5e87"` including the quotes.
+///
+/// If `weak` (a `bool`) is given and `true` the tag is prefixed with
+/// `W/`, marking it as weak. Without it the tag is strong.
+///
+/// Example usage:
+/// ```rust
+/// use const_str_join::declare_etag_str;
+///
+/// const STRONG: &'static str = declare_etag_str!(["a", "b", "c"], ",");
+/// assert_eq!(STRONG, "\"7a8f5e87\"");
+/// const WEAK: &'static str = declare_etag_str!(["a", "b", "c"], ",", true);
+/// assert_eq!(WEAK, "W/\"7a8f5e87\"");
+/// ```
+#[macro_export]
+macro_rules! declare_etag_str {
+    ($array:expr, $sep:expr) => {
+        $crate::declare_etag_str!($array, $sep, false)
+    };
+    ($array:expr, $sep:expr, $weak:expr) => {
+        const {
+            const __JOINED: &str = $crate::const_join!($array, $sep);
+            const __PREFIX: &str = if $weak { "W/" } else { "" };
+            const __HASH: u32 = $crate::fnv1a(__JOINED.as_bytes());
+            $crate::__build_str!(__PREFIX.len() + 10, |buffer| {
+                let op = $crate::copy_bytes(__PREFIX.as_bytes(), &mut buffer, 0);
+                let op = $crate::copy_bytes(b"\"", &mut buffer, op);
+                let op = $crate::write_hex_u32(__HASH, &mut buffer, op);
+                $crate::copy_bytes(b"\"", &mut buffer, op)
+            })
+        }
+    };
+}
+
+/// Copies the bytes of `src` into `dest` at `offset` and returns the
+/// offset right after them.
+///
+/// Unlike the internal writers any existing content of `dest` is simply
+/// overwritten. Panics if `src` doesn't fit into `dest` at `offset`.
+///
+/// ```rust
+/// const GREETING: [u8; 11] = {
+///     let mut buffer = [b' '; 11];
+///     let offset = const_str_join::copy_str_into("hello", &mut buffer, 0);
+///     const_str_join::copy_str_into("world", &mut buffer, offset + 1);
+///     buffer
+/// };
+/// assert_eq!(&GREETING, b"hello world");
+/// ```
+pub const fn copy_str_into(src: &str, dest: &mut [u8], offset: usize) -> usize {
+    assert!(
+        offset <= dest.len() && src.len() <= dest.len() - offset,
+        "source string doesn't fit into the destination buffer"
+    );
+    let bytes = src.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        dest[offset + i] = bytes[i];
+        i += 1;
+    }
+    offset + bytes.len()
+}
+
+#[doc(hidden)]
+pub const fn two_level_joined_size(groups: &[&[&str]], unit_sep: &str, record_sep: &str) -> usize {
+    let mut n = if groups.is_empty() {
+        0
+    } else {
+        (groups.len() - 1) * record_sep.len()
+    };
+    let mut i = 0;
+    while i < groups.len() {
+        n += required_size(get_elem::<&[&str]>(groups, i), Some(unit_sep));
+        i += 1;
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_two_level_joined(
+    groups: &[&[&str]],
+    unit_sep: &str,
+    record_sep: &str,
+    output: &mut [u8],
+) -> usize {
+    let mut op = 0;
+    let mut i = 0;
+    while i < groups.len() {
+        if i > 0 {
+            op = copy_bytes(record_sep.as_bytes(), output, op);
+        }
+        op = write_joined(get_elem::<&[&str]>(groups, i), Some(unit_sep), output, op);
+        i += 1;
+    }
+    op
+}
+
+/// Joins the elements of every group with `unit_sep` and the groups with
+/// `record_sep`.
+///
+/// Groups may have different lengths and may be empty, an empty group
+/// still gets its record separators.
+///
+/// Example usage:
+/// ```rust
+/// const S: &'static str = const_str_join::declare_two_level_join_str!([["a", "b"], ["c", "d"]], ",", ";");
+/// assert_eq!(S, "a,b;c,d");
+///
+/// const GROUPS: &[&[&str]] = &[&["x"], &["y", "z"]];
+/// const T: &'static str = const_str_join::declare_two_level_join_str!(GROUPS, ' ', '|');
+/// assert_eq!(T, "x|y z");
+/// ```
+#[macro_export]
+macro_rules! declare_two_level_join_str {
+    ([$($group:expr),* $(,)?], $unit_sep:expr, $record_sep:expr) => {
+        const {
+            // the annotated type allows groups of different lengths
+            const __GROUPS: &[&[&str]] = &[$(&$group),*];
+            $crate::declare_two_level_join_str!(__GROUPS, $unit_sep, $record_sep)
+        }
+    };
+    ($groups:expr, $unit_sep:expr, $record_sep:expr) => {
+        const {
+            const __UNIT_SEP: &str = $crate::__separator!($unit_sep);
+            const __RECORD_SEP: &str = $crate::__separator!($record_sep);
+            $crate::__build_str!(
+                $crate::two_level_joined_size($groups, __UNIT_SEP, __RECORD_SEP),
+                |buffer| $crate::write_two_level_joined($groups, __UNIT_SEP, __RECORD_SEP, &mut buffer)
+            )
+        }
+    };
+}
+
+const fn abbreviate(s: &str, mut output: Option<&mut [u8]>) -> usize {
+    let bytes = s.as_bytes();
+    let mut op = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let word_start = i == 0 || bytes[i - 1].is_ascii_whitespace();
+        if word_start && bytes[i].is_ascii_alphabetic() {
+            if let Some(output) = &mut output {
+                output[op] = bytes[i].to_ascii_uppercase();
+            }
+            op += 1;
+        }
+        i += 1;
+    }
+    op
+}
+
+#[doc(hidden)]
+pub const fn abbreviated_size(s: &str) -> usize {
+    abbreviate(s, None)
+}
+
+#[doc(hidden)]
+pub const fn write_abbreviated(s: &str, output: &mut [u8]) -> usize {
+    abbreviate(s, Some(output))
+}
+
+/// Abbreviates `s` to the uppercased initials of its words.
+///
+/// Words are separated by ASCII whitespace, just like for
+/// [`title_case_str!`]. Words that don't start with an ASCII letter,
+/// such as numbers, are skipped entirely.
+///
+/// Example usage:
+/// ```rust
+/// const MVC: &'static str = const_str_join::abbreviate_str!("Model View Controller");
+/// assert_eq!(MVC, "MVC");
+/// ```
+#[macro_export]
+macro_rules! abbreviate_str {
+    ($s:expr) => {
+        const {
+            const __INPUT: &str = $s;
+            $crate::__build_str!($crate::abbreviated_size(__INPUT), |buffer| {
+                $crate::write_abbreviated(__INPUT, &mut buffer)
+            })
+        }
+    };
+}
+
+const fn group_digits(digits: &[u8], sep: &str, mut output: Option<&mut [u8]>) -> usize {
+    let mut op = 0;
+    let mut i = 0;
+    while i < digits.len() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            if let Some(output) = &mut output {
+                copy_bytes(sep.as_bytes(), output, op);
+            }
+            op += sep.len();
+        }
+        if let Some(output) = &mut output {
+            output[op] = digits[i];
+        }
+        op += 1;
+        i += 1;
+    }
+    op
+}
+
+/// Wrapper used to accept either a `&str` of ASCII digits or a `usize`
+/// in [`group_digits_str!`], see [`Separator`] for why.
+#[doc(hidden)]
+pub struct DigitGroups<T>(pub T);
+
+impl DigitGroups<&str> {
+    const fn digits(&self) -> &[u8] {
+        let bytes = self.0.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            assert!(
+                bytes[i].is_ascii_digit(),
+                "number must only consist of ASCII digits"
+            );
+            i += 1;
+        }
+        bytes
+    }
+
+    pub const fn grouped_size(&self, sep: &str) -> usize {
+        group_digits(self.digits(), sep, None)
+    }
+
+    pub const fn write_grouped(&self, sep: &str, output: &mut [u8]) -> usize {
+        group_digits(self.digits(), sep, Some(output))
+    }
+}
+
+impl DigitGroups<usize> {
+    pub const fn grouped_size(&self, sep: &str) -> usize {
+        let len = decimal_len(self.0 as u64);
+        len + (len - 1) / 3 * sep.len()
+    }
+
+    pub const fn write_grouped(&self, sep: &str, output: &mut [u8]) -> usize {
+        let mut buffer = [0u8; 20];
+        let len = write_decimal(self.0 as u64, &mut buffer, 0);
+        group_digits(buffer.split_at(len).0, sep, Some(output))
+    }
+}
+
+/// Inserts `sep` between every group of three digits of `n`, counted
+/// from the right.
+///
+/// `n` is either a `usize` or a `&str` that must only consist of ASCII
+/// digits (no sign, no decimal point).
+///
+/// Example usage:
+/// ```rust
+/// use const_str_join::group_digits_str;
+///
+/// assert_eq!(group_digits_str!("1234567", ","), "1,234,567");
+/// assert_eq!(group_digits_str!(1234567, '_'), "1_234_567");
+/// ```
+///
+/// ```compile_fail
+/// let s = const_str_join::group_digits_str!("-1234", ",");
+/// ```
+#[macro_export]
+macro_rules! group_digits_str {
+    ($n:expr, $sep:expr) => {
+        const {
+            const __SEP: &str = $crate::__separator!($sep);
+            $crate::__build_str!($crate::DigitGroups($n).grouped_size(__SEP), |buffer| {
+                $crate::DigitGroups($n).write_grouped(__SEP, &mut buffer)
+            })
+        }
+    };
+}
+
+/// Returns whether `s` starts with `prefix`.
+///
+/// ```rust
+/// const _: () = assert!(const_str_join::starts_with("--help", "--"));
+/// const _: () = assert!(!const_str_join::starts_with("-h", "--"));
+/// ```
+pub const fn starts_with(s: &str, prefix: &str) -> bool {
+    bytes_match_at(s.as_bytes(), 0, prefix.as_bytes())
+}
+
+#[doc(hidden)]
+pub const fn prefix_filtered_size(inputs: &[&str], sep: &str, prefix: &str) -> usize {
+    let mut n = 0;
+    let mut matched = 0;
+    let mut i = 0;
+    while i < inputs.len() {
+        if starts_with(get_str(inputs, i), prefix) {
+            if matched > 0 {
+                n += sep.len();
+            }
+            n += get_str(inputs, i).len();
+            matched += 1;
+        }
+        i += 1;
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_prefix_filtered(
+    inputs: &[&str],
+    sep: &str,
+    prefix: &str,
+    output: &mut [u8],
+) -> usize {
+    let mut op = 0;
+    let mut matched = 0;
+    let mut i = 0;
+    while i < inputs.len() {
+        if starts_with(get_str(inputs, i), prefix) {
+            if matched > 0 {
+                op = copy_bytes(sep.as_bytes(), output, op);
+            }
+            op = copy_bytes(get_str(inputs, i).as_bytes(), output, op);
+            matched += 1;
+        }
+        i += 1;
+    }
+    op
+}
+
+/// Joins the elements of `array` that start with `prefix` with `sep`,
+/// all other elements are left out.
+///
+/// The prefix is kept as part of the elements.
+///
+/// Example usage:
+/// ```rust
+/// const FLAGS: &'static str = const_str_join::declare_joined_with_prefix_filter_str!(["--a", "x", "--b"], " ", "--");
+/// assert_eq!(FLAGS, "--a --b");
+/// ```
+#[macro_export]
+macro_rules! declare_joined_with_prefix_filter_str {
+    ($array:expr, $sep:expr, $prefix:expr) => {
+        const {
+            const __SEP: &str = $crate::__separator!($sep);
+            const __PREFIX: &str = $prefix;
+            $crate::__build_str!(
+                $crate::prefix_filtered_size(&$array, __SEP, __PREFIX),
+                |buffer| $crate::write_prefix_filtered(&$array, __SEP, __PREFIX, &mut buffer)
+            )
+        }
+    };
+}
+
+/// Returns the number of chars of `inputs` joined with `sep`, without
+/// building the joined string.
+///
+/// ```rust
+/// use const_str_join::joined_char_count;
+/// assert_eq!(joined_char_count(&["äö", "ü"], "→"), 4);
+/// assert_eq!(joined_char_count(&[], ","), 0);
+/// ```
+pub const fn joined_char_count(inputs: &[&str], sep: &str) -> usize {
+    let mut n = if inputs.is_empty() {
+        0
+    } else {
+        (inputs.len() - 1) * char_count(sep)
+    };
+    let mut i = 0;
+    while i < inputs.len() {
+        n += char_count(get_str(inputs, i));
+        i += 1;
+    }
+    n
+}
+
+/// Returns the number of chars of `array` joined with `sep` (a `&str` or
+/// a `char`) as a `usize` constant, see [`joined_char_count`].
+///
+/// Example usage:
+/// ```rust
+/// const WIDTH: usize = const_str_join::joined_char_count!(["größe", "maß"], '·');
+/// assert_eq!(WIDTH, 9);
+/// ```
+#[macro_export]
+macro_rules! joined_char_count {
+    ($array:expr, $sep:expr) => {
+        const { $crate::joined_char_count(&$array, $crate::__separator!($sep)) }
+    };
+}
+
+/// Returns the number of `#` a Rust raw string literal containing `s`
+/// needs: one more than the longest run of `#` following a `"` in `s`,
+/// or none at all if `s` doesn't contain a `"`.
+#[doc(hidden)]
+pub const fn raw_str_hashes(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let mut hashes = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'"' {
+            let mut run = 0;
+            while i + 1 + run < bytes.len() && bytes[i + 1 + run] == b'#' {
+                run += 1;
+            }
+            if run + 1 > hashes {
+                hashes = run + 1;
+            }
+        }
+        i += 1;
+    }
+    hashes
+}
+
+#[doc(hidden)]
+pub const fn write_raw_str_literal(s: &str, hashes: usize, output: &mut [u8]) -> usize {
+    let mut op = copy_bytes(b"r", output, 0);
+    op = write_repeated("#", hashes, output, op);
+    op = copy_bytes(b"\"", output, op);
+    op = copy_bytes(s.as_bytes(), output, op);
+    op = copy_bytes(b"\"", output, op);
+    write_repeated("#", hashes, output, op)
+}
+
+/// Joins `array` with `sep` and wraps the result in a Rust raw string
+/// literal, e.g. for writing generated `.rs` files.
+///
+/// The literal uses as few `#` as possible while making sure the
+/// content can't end it early, see the examples.
+///
+/// Example usage:
+/// ```rust
+/// use const_str_join::declare_rust_raw_str_literal;
+///
+/// const PLAIN: &'static str = declare_rust_raw_str_literal!(["C:", "dir"], '\\');
+/// assert_eq!(PLAIN, r#"r"C:\dir""#);
+/// const QUOTED: &'static str = declare_rust_raw_str_literal!(["say", "\"hi\""], ' ');
+/// assert_eq!(QUOTED, r###"r#"say "hi""#"###);
+/// ```
+#[macro_export]
+macro_rules! declare_rust_raw_str_literal {
+    ($array:expr, $sep:expr) => {
+        const {
+            const __JOINED: &str = $crate::const_join!($array, $sep);
+            const __HASHES: usize = $crate::raw_str_hashes(__JOINED);
+            $crate::__build_str!(__JOINED.len() + 3 + 2 * __HASHES, |buffer| {
+                $crate::write_raw_str_literal(__JOINED, __HASHES, &mut buffer)
+            })
+        }
+    };
+}
+
+#[doc(hidden)]
+pub const fn write_swapped_case(s: &str, output: &mut [u8]) -> usize {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        output[i] = match bytes[i] {
+            b @ b'a'..=b'z' => b.to_ascii_uppercase(),
+            b @ b'A'..=b'Z' => b.to_ascii_lowercase(),
+            b => b,
+        };
+        i += 1;
+    }
+    i
+}
+
+/// Swaps the case of every ASCII letter in `s`, leaving all other chars
+/// untouched.
+///
+/// Applying it twice yields the original string.
+///
+/// Example usage:
+/// ```rust
+/// const S: &'static str = const_str_join::swap_case_str!("Hello");
+/// assert_eq!(S, "hELLO");
+/// ```
+#[macro_export]
+macro_rules! swap_case_str {
+    ($s:expr) => {
+        const {
+            const __INPUT: &str = $s;
+            $crate::__build_str!(__INPUT.len(), |buffer| {
+                $crate::write_swapped_case(__INPUT, &mut buffer)
+            })
+        }
+    };
+}
+
+/// Joins `array` alternating between two separators: `even` goes into
+/// the first gap and every other one after it (gaps `0`, `2`, ...),
+/// `odd` into the remaining ones.
+///
+/// This is [`declare_joined_cycle_sep_str!`] with a cycle of two.
+///
+/// Example usage:
+/// ```rust
+/// const PAIRS: &'static str = const_str_join::declare_joined_alt_sep_str!(["a", "b", "c", "d"], "-", " ");
+/// assert_eq!(PAIRS, "a-b c-d");
+/// ```
+#[macro_export]
+macro_rules! declare_joined_alt_sep_str {
+    ($array:expr, $even:expr, $odd:expr) => {
+        $crate::declare_joined_cycle_sep_str!(
+            $array,
+            [$crate::__separator!($even), $crate::__separator!($odd)]
+        )
+    };
+}
+
+#[doc(hidden)]
+pub const fn fixed_record_size(fields: &[(&str, usize)]) -> usize {
+    let mut n = 0;
+    let mut i = 0;
+    while i < fields.len() {
+        n += get_elem(fields, i).1;
+        i += 1;
+    }
+    n
+}
+
+#[doc(hidden)]
+pub const fn write_fixed_record(fields: &[(&str, usize)], fill: char, output: &mut [u8]) -> usize {
+    assert!(fill.is_ascii(), "fill char must be ASCII");
+    let mut op = 0;
+    let mut i = 0;
+    while i < fields.len() {
+        let (s, width) = *get_elem(fields, i);
+        let len = prev_char_boundary(s, if s.len() < width { s.len() } else { width });
+        let (head, _) = s.as_bytes().split_at(len);
+        op = copy_bytes(head, output, op);
+        let mut n = len;
+        while n < width {
+            output[op] = fill as u8;
+            op += 1;
+            n += 1;
+        }
+        i += 1;
+    }
+    op
+}
+
+/// Concatenates `(field, width)` pairs into a fixed-format record where
+/// every field takes up exactly `width` bytes.
+///
+/// Shorter fields are padded on the right with `fill`, which has to be an
+/// ASCII `char`. Longer ones are truncated at the last char boundary
+/// within `width` and padded from there, so multi-byte chars are never
+/// split.
+///
+/// Example usage:
+/// ```rust
+/// const RECORD: &'static str = const_str_join::declare_fixed_record_str!([("name", 6), ("code", 2)], ' ');
+/// assert_eq!(RECORD, "name  co");
+/// ```
+#[macro_export]
+macro_rules! declare_fixed_record_str {
+    ($fields:expr, $fill:expr) => {
+        const {
+            const __FIELDS: &[(&str, usize)] = &$fields;
+            $crate::__build_str!($crate::fixed_record_size(__FIELDS), |buffer| {
+                $crate::write_fixed_record(__FIELDS, $fill, &mut buffer)
+            })
+        }
+    };
+}
+
+const fn more_suffix(
+    suffix: &str,
+    omitted: usize,
+    mut output: Option<&mut [u8]>,
+    offset: usize,
+) -> usize {
+    let bytes = suffix.as_bytes();
+    let mut op = offset;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes_match_at(bytes, i, b"{n}") {
+            if let Some(output) = &mut output {
+                write_decimal(omitted as u64, output, op);
+            }
+            op += decimal_len(omitted as u64);
+            i += 3;
+        } else {
+            if let Some(output) = &mut output {
+                output[op] = bytes[i];
+            }
+            op += 1;
+            i += 1;
+        }
+    }
+    op
+}
+
+#[doc(hidden)]
+pub const fn joined_with_more_size(inputs: &[&str], sep: &str, max: usize, suffix: &str) -> usize {
+    if inputs.len() <= max {
+        return required_size(inputs, Some(sep));
+    }
+    let shown = required_size(inputs.split_at(max).0, Some(sep));
+    more_suffix(suffix, inputs.len() - max, None, shown)
+}
+
+#[doc(hidden)]
+pub const fn write_joined_with_more(
+    inputs: &[&str],
+    sep: &str,
+    max: usize,
+    suffix: &str,
+    output: &mut [u8],
+) -> usize {
+    if inputs.len() <= max {
+        return write_joined(inputs, Some(sep), output, 0);
+    }
+    let op = write_joined(inputs.split_at(max).0, Some(sep), output, 0);
+    more_suffix(suffix, inputs.len() - max, Some(output), op)
+}
+
+/// Joins at most the first `max` elements of `array` with `sep`.
+///
+/// If elements had to be left out `suffix` is appended, with every `{n}`
+/// in it replaced by the number of omitted elements.
+///
+/// Example usage:
+/// ```rust
+/// use const_str_join::declare_joined_with_more_str;
+///
+/// const LABEL: &'static str = declare_joined_with_more_str!(["a", "b", "c", "d"], ", ", 2, " (+{n} more)");
+/// assert_eq!(LABEL, "a, b (+2 more)");
+/// const ALL: &'static str = declare_joined_with_more_str!(["a", "b"], ", ", 2, " (+{n} more)");
+/// assert_eq!(ALL, "a, b");
+/// ```
+#[macro_export]
+macro_rules! declare_joined_with_more_str {
+    ($array:expr, $sep:expr, $max:expr, $suffix:expr) => {
+        const {
+            const __SEP: &str = $crate::__separator!($sep);
+            const __MAX: usize = $max;
+            const __SUFFIX: &str = $suffix;
+            $crate::__build_str!(
+                $crate::joined_with_more_size(&$array, __SEP, __MAX, __SUFFIX),
+                |buffer| $crate::write_joined_with_more(
+                    &$array,
+                    __SEP,
+                    __MAX,
+                    __SUFFIX,
+                    &mut buffer
+                )
+            )
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: &str = "A";
+    const B: &str = "B";
+    const C: &str = "C";
+    const ARRAY_OF_STRINGS: [&str; 3] = [A, B, C];
+
+    #[test]
+    fn nested() {
+        const FOO: &str = const_join!(ARRAY_OF_STRINGS, ":");
+        const MORE_PARTS: [&str; 3] = ["<", FOO, ">"];
+        const MORE: &str = const_join!(MORE_PARTS, "");
+        // clippy ICEs when const-evaluating a named const that points at a
+        // static, compare through locals instead.
+        let (foo, more) = (FOO, MORE);
+        assert_eq!(foo, "A:B:C");
+        assert_eq!(more, "<A:B:C>");
+    }
+
+    #[test]
+    fn joined_array() {
+        let s = joined_array!(ARRAY_OF_STRINGS, "-");
+        assert_eq!(&s, b"A-B-C");
+    }
+
+    #[test]
+    fn char_separator() {
+        let s = const_join!(ARRAY_OF_STRINGS, ',');
+        assert_eq!(s, "A,B,C");
+        let s = joined_array!(ARRAY_OF_STRINGS, '-');
+        assert_eq!(&s, b"A-B-C");
+    }
+
+    #[test]
+    fn multi_byte_char_separator() {
+        const SEP: char = '→';
+        let s = const_join!(ARRAY_OF_STRINGS, SEP);
+        assert_eq!(s, "A→B→C");
+        assert_eq!(s.len(), 3 + 2 * SEP.len_utf8());
+    }
+
+    #[test]
+    fn str_and_char_separator_agree() {
+        assert_eq!(
+            const_join!(ARRAY_OF_STRINGS, "é"),
+            const_join!(ARRAY_OF_STRINGS, 'é')
+        );
+        assert_eq!(Separator("é").sep_len(), Separator('é').sep_len());
+    }
+
+    #[test]
+    fn columns() {
+        let s = declare_columns_str!(["a", "bbb", "cc"], " ");
+        assert_eq!(s, "a   bbb cc ");
+        let s = declare_columns_str!(["ä", "abc", "ñó"], '|');
+        assert_eq!(s, "ä  |abc|ñó ");
+        let s = declare_columns_str!(["abc"], ",");
+        assert_eq!(s, "abc");
+        let s = declare_columns_str!([], ",");
+        assert_eq!(s, "");
+    }
+
+    #[test]
+    fn ascii() {
+        assert!(is_ascii("Hello, World!"));
+        assert!(is_ascii(""));
+        assert!(!is_ascii("Grüße"));
+    }
+
+    #[test]
+    fn prefix_each() {
+        let s = declare_joined_prefix_each_str!(["a.txt", "b.txt"], " ", "-f ");
+        assert_eq!(s, "-f a.txt -f b.txt");
+        let s = declare_joined_prefix_each_str!(["a.txt"], " ", "-f ");
+        assert_eq!(s, "-f a.txt");
+        let s = declare_joined_prefix_each_str!([], " ", "-f ");
+        assert_eq!(s, "");
+    }
+
+    #[test]
+    fn env() {
+        let s = declare_env_str!(["/usr/bin", "/bin"], ':');
+        assert_eq!(s, "/usr/bin:/bin");
+        let s = declare_env_pairs_str!([("KEY", "val"), ("FOO", "bar")]);
+        assert_eq!(s, "KEY=val\nFOO=bar");
+        let s = declare_env_pairs_str!([("KEY", "")]);
+        assert_eq!(s, "KEY=");
+        let s = declare_env_pairs_str!([("KEY", ""), ("FOO", "bar")]);
+        assert_eq!(s, "KEY=\nFOO=bar");
+    }
+
+    #[test]
+    fn strip_ansi() {
+        let s = strip_ansi_str!("\x1b[32mgreen\x1b[0m");
+        assert_eq!(s, "green");
+        let s = strip_ansi_str!("\x1b[1;31m\x1b[0m");
+        assert_eq!(s, "");
+        let s = strip_ansi_str!("no escapes [here]");
+        assert_eq!(s, "no escapes [here]");
+    }
+
+    #[test]
+    fn repeat() {
+        let s = repeat_str!("ab", 3);
+        assert_eq!(s, "ababab");
+        let s = repeat_str!("ab", 0);
+        assert_eq!(s, "");
+        let s = declare_lines!(["a", "b", "c"]);
+        assert_eq!(s, "a\nb\nc");
+    }
+
+    #[test]
+    fn fenced() {
+        let s = repeat_join_around!("body", "=", 10);
+        assert_eq!(s, "==========\nbody\n==========");
+        let s = repeat_join_around!("line one\nline two", "-", 3);
+        assert_eq!(s, "---\nline one\nline two\n---");
+    }
+
+    #[test]
+    fn char_boundaries() {
+        // "é" is 2 bytes, "😀" is 4 bytes
+        const S: &str = "aé😀";
+        assert_eq!(next_char_boundary(S, 0), 0);
+        assert_eq!(next_char_boundary(S, 2), 3);
+        assert_eq!(prev_char_boundary(S, 2), 1);
+        assert_eq!(next_char_boundary(S, 4), 7);
+        assert_eq!(next_char_boundary(S, 6), 7);
+        assert_eq!(prev_char_boundary(S, 4), 3);
+        assert_eq!(prev_char_boundary(S, 6), 3);
+        assert_eq!(next_char_boundary(S, 7), 7);
+        assert_eq!(prev_char_boundary(S, 7), 7);
+        assert_eq!(next_char_boundary(S, 100), 7);
+        assert_eq!(prev_char_boundary(S, 100), 7);
+    }
+
+    #[test]
+    fn map_prefix() {
+        let s = declare_joined_map_prefix_str!(["a", "b"], "_", "pkg_");
+        assert_eq!(s, "PKG_A_PKG_B");
+        let s = declare_joined_map_prefix_str!(["A", "B"], "_", "PKG_");
+        assert_eq!(s, "PKG_A_PKG_B");
+        let s = declare_joined_map_prefix_str!(["ä"], "_", "x");
+        assert_eq!(s, "Xä");
+    }
+
+    #[test]
+    fn replace_bytes() {
+        let s = replace_bytes!(b"a\x00b\x00c", b"\x00", b"--");
+        assert_eq!(&s, b"a--b--c");
+        let s = replace_bytes!(b"\xff\xfe", b"\xfe", b"\x00\x01\x02");
+        assert_eq!(&s, b"\xff\x00\x01\x02");
+        let s = replace_bytes!(b"aaa", b"aa", b"b");
+        assert_eq!(&s, b"ba");
+        let s = replace_bytes!(b"abc", b"x", b"y");
+        assert_eq!(&s, b"abc");
+    }
+
+    #[test]
+    fn collapse_sep() {
+        let s = declare_joined_collapse_sep_str!(["a", "", "b"], ",");
+        assert_eq!(s, "a,b");
+        let s = declare_joined_collapse_sep_str!(["", "", "a", "", ""], ",");
+        assert_eq!(s, ",a,");
+        let s = declare_joined_collapse_sep_str!(["a::", "::b"], "::");
+        assert_eq!(s, "a::b");
+        let s = declare_joined_collapse_sep_str!(["a", "b"], "");
+        assert_eq!(s, "ab");
+    }
+
+    #[test]
+    fn required_size_none_and_empty_agree() {
+        assert_eq!(required_size(&ARRAY_OF_STRINGS, None), 3);
+        assert_eq!(required_size(&ARRAY_OF_STRINGS, Some("")), 3);
+        assert_eq!(required_size(&ARRAY_OF_STRINGS, non_empty_sep("")), 3);
+        assert_eq!(required_size(&ARRAY_OF_STRINGS, Some("--")), 7);
+        assert_eq!(required_size(&[], Some("--")), 0);
+        assert_eq!(concated_size(ARRAY_OF_STRINGS, "--"), 7);
+    }
+
+    #[test]
+    fn user_consts_are_not_shadowed() {
+        const SEP: &str = "+";
+        const INPUT: &str = "\x1b[1mbold\x1b[0m";
+        assert_eq!(const_join!(ARRAY_OF_STRINGS, SEP), "A+B+C");
+        assert_eq!(declare_columns_str!(["a", "bb"], SEP), "a +bb");
+        assert_eq!(strip_ansi_str!(INPUT), "bold");
+    }
+
+    #[test]
+    fn joined_bytes() {
+        let s: &'static [u8] = declare_joined_bytes!(ARRAY_OF_STRINGS, ", ");
+        assert_eq!(s, b"A, B, C");
+        let s = declare_joined_bytes!(["ä", "b"], '/');
+        assert_eq!(s, "ä/b".as_bytes());
+    }
+
+    #[test]
+    fn csv_row() {
+        let s = declare_csv_row_str!(["a", "b", "c"]);
+        assert_eq!(s, "a,b,c");
+        let s = declare_csv_row_str!(["a", "b,c"]);
+        assert_eq!(s, "a,\"b,c\"");
+        let s = declare_csv_row_str!(["say \"hi\"", "line\nbreak"]);
+        assert_eq!(s, "\"say \"\"hi\"\"\",\"line\nbreak\"");
+        let s = declare_csv_row_str!(["", ""]);
+        assert_eq!(s, ",");
+    }
+
+    #[test]
+    fn separator_first() {
+        assert_eq!(
+            const_join!(sep = ",", ARRAY_OF_STRINGS),
+            const_join!(ARRAY_OF_STRINGS, ",")
+        );
+        let s = const_join!(sep = '/', ["usr", "local", "bin"]);
+        assert_eq!(s, "usr/local/bin");
+        let s = joined_array!(sep = "-", ARRAY_OF_STRINGS);
+        assert_eq!(&s, b"A-B-C");
+    }
+
+    #[test]
+    fn utf8_validation() {
+        assert!(is_valid_utf8(b"plain ascii"));
+        assert_eq!(first_invalid_index(b"plain ascii"), None);
+        assert!(is_valid_utf8("→ä😀".as_bytes()));
+        assert_eq!(first_invalid_index("→ä😀".as_bytes()), None);
+        // "😀" cut after its third byte
+        assert!(!is_valid_utf8(b"ab\xf0\x9f\x98"));
+        assert_eq!(first_invalid_index(b"ab\xf0\x9f\x98"), Some(2));
+        assert!(is_valid_utf8(b""));
+    }
+
+    #[test]
+    fn decimal() {
+        assert_eq!(decimal_len(0), 1);
+        assert_eq!(decimal_len(9), 1);
+        assert_eq!(decimal_len(10), 2);
+        assert_eq!(decimal_len(u64::MAX), u64::MAX.ilog10() as usize + 1);
+        assert_eq!(__usize_str!(0), "0");
+        assert_eq!(__usize_str!(1234), "1234");
+    }
+
+    #[test]
+    fn ident() {
+        let s = declare_ident_str!(["my", "field"], "_");
+        assert_eq!(s, "my_field");
+        let s = declare_ident_str!(["_private", "2"], "");
+        assert_eq!(s, "_private2");
+        assert_eq!(first_invalid_ident_index("1x"), Some(0));
+        assert_eq!(first_invalid_ident_index("my field"), Some(2));
+        assert_eq!(first_invalid_ident_index(""), Some(0));
+    }
+
+    #[test]
+    fn trim_matches() {
+        assert_eq!(trim_matches_str!("***hi***", "*"), "hi");
+        assert_eq!(trim_matches_str!("*****", "*"), "");
+        assert_eq!(trim_matches_str!("", "*"), "");
+        assert_eq!(trim_matches_str!("hi", ""), "hi");
+        assert_eq!(trim_matches_str!("«*hi*»", "«»*"), "hi");
+        // "é" and "è" share their first byte, only whole chars must match
+        assert_eq!(trim_matches_str!("éaè", "è"), "éa");
+    }
+
+    #[test]
+    fn fill_to_width() {
+        assert_eq!(fill_to_width_str!("-=", 7), "-=-=-=-");
+        assert_eq!(fill_to_width_str!("-=", 6), "-=-=-=");
+        assert_eq!(fill_to_width_str!("abc", 2), "ab");
+        assert_eq!(fill_to_width_str!("", 0), "");
+    }
+
+    #[test]
+    fn build() {
+        let s = build_str!("[", join(ARRAY_OF_STRINGS, ","), "]");
+        assert_eq!(s, "[A,B,C]");
+        let s = build_str!(A, join(["x", "y"], '/'), repeat("!", 3), B);
+        assert_eq!(s, "Ax/y!!!B");
+        let s = build_str!("(", join([], ","), ")");
+        assert_eq!(s, "()");
+        let s = build_str!();
+        assert_eq!(s, "");
+    }
+
+    #[test]
+    fn char_search() {
+        assert!(contains_char("a,b", ','));
+        assert!(!contains_char("a;b", ','));
+        assert_eq!(count_char("a,b,,c", ','), 3);
+        assert_eq!(count_char("", ','), 0);
+        assert!(contains_char("résumé", 'é'));
+        assert_eq!(count_char("résumé", 'é'), 2);
+        // 'è' shares the leading byte with 'é'
+        assert!(!contains_char("résumé", 'è'));
+        assert_eq!(count_char("résumé", 'è'), 0);
+    }
+
+    #[test]
+    fn unique_assert() {
+        let s = declare_joined_unique_assert_str!(["a", "b", "c"], ",");
+        assert_eq!(s, "a,b,c");
+        assert_eq!(first_duplicate(&["a", "b", "c"]), None);
+        assert_eq!(first_duplicate(&["a", "b", "c", "b", "a"]), Some((0, 4)));
+        assert_eq!(first_duplicate(&["a", "b", "b"]), Some((1, 2)));
+    }
+
+    #[test]
+    fn join_mode() {
+        assert_eq!(
+            declare_joined_mode_str!(ARRAY_OF_STRINGS, ",", JoinMode::Between),
+            "A,B,C"
+        );
+        assert_eq!(
+            declare_joined_mode_str!(ARRAY_OF_STRINGS, ",", JoinMode::Leading),
+            ",A,B,C"
+        );
+        assert_eq!(
+            declare_joined_mode_str!(ARRAY_OF_STRINGS, ",", JoinMode::Trailing),
+            "A,B,C,"
+        );
+        assert_eq!(
+            declare_joined_mode_str!(ARRAY_OF_STRINGS, ",", JoinMode::Surrounding),
+            ",A,B,C,"
+        );
+        assert_eq!(
+            declare_joined_mode_str!(["a"], '|', JoinMode::Surrounding),
+            "|a|"
+        );
+        assert_eq!(declare_joined_mode_str!([], '|', JoinMode::Surrounding), "");
+    }
+
+    #[test]
+    fn inline_macro_elements() {
+        let s = const_join!([env!("CARGO_PKG_NAME"), "-", env!("CARGO_PKG_VERSION")], "");
+        assert_eq!(
+            s,
+            concat!(env!("CARGO_PKG_NAME"), "-", env!("CARGO_PKG_VERSION"))
+        );
+        let s = const_join!([A, B,], '+');
+        assert_eq!(s, "A+B");
+    }
+
+    #[test]
+    fn wrap() {
+        assert_eq!(wrap_str!("the quick brown fox", 9), "the quick\nbrown fox");
+        // "abcdefghi" exactly fills the line
+        assert_eq!(wrap_str!("abcdefghi x", 9), "abcdefghi\nx");
+        assert_eq!(wrap_str!("a abcdefghijkl b", 5), "a\nabcdefghijkl\nb");
+        assert_eq!(wrap_str!("äöü äöü", 7), "äöü äöü");
+        assert_eq!(wrap_str!("äöü äöü", 6), "äöü\näöü");
+        assert_eq!(wrap_str!("ab\ncd ef", 5), "ab\ncd ef");
+        assert_eq!(wrap_str!("", 5), "");
+    }
+
+    #[test]
+    fn masked_join() {
+        let s = declare_joined_masked_str!(["a", "b", "c"], ",", [true, false]);
+        assert_eq!(s, "a,bc");
+        let s = declare_joined_masked_str!(["a", "b", "c", "d"], "--", [false, true, true]);
+        assert_eq!(s, "ab--c--d");
+        let s = declare_joined_masked_str!(["a"], ",", []);
+        assert_eq!(s, "a");
+    }
+
+    #[test]
+    fn bom() {
+        let s = declare_joined_bom_str!(ARRAY_OF_STRINGS, ",");
+        assert!(s.as_bytes().starts_with(b"\xEF\xBB\xBF"));
+        assert_eq!(
+            s.strip_prefix('\u{FEFF}'),
+            Some(const_join!(ARRAY_OF_STRINGS, ","))
+        );
+    }
+
+    #[test]
+    fn zip() {
+        let s = zip_join!(["a", "b", "c"], ["1", "2"], "=", ",", "?");
+        assert_eq!(s, "a=1,b=2,c=?");
+        let s = zip_join!(["a"], ["1", "2", "3"], ':', ';', "-");
+        assert_eq!(s, "a:1;-:2;-:3");
+        let s = zip_join!(["a", "b"], ["1", "2"], "=", "&", "");
+        assert_eq!(s, "a=1&b=2");
+        let s = zip_join!([], [], "=", ",", "?");
+        assert_eq!(s, "");
+    }
+
+    #[test]
+    fn common_prefix() {
+        assert_eq!(common_prefix_len(&["abc", "abc", "abc"]), 3);
+        assert_eq!(common_prefix_len(&["abc"]), 3);
+        assert_eq!(common_prefix_len(&["abc", "xbc"]), 0);
+        assert_eq!(common_prefix_len(&[]), 0);
+        // "é" and "è" share their first byte
+        assert_eq!(common_prefix_len(&["aé", "aè"]), 1);
+        assert_eq!(common_prefix_str!(["app::a", "app::b"]), "app::");
+        assert_eq!(common_prefix_str!(["abc"]), "abc");
+        assert_eq!(common_prefix_str!([]), "");
+    }
+
+    #[test]
+    fn grouped() {
+        let s = declare_grouped_str!(["1", "2", "3", "4", "5"], 2, "", " ");
+        assert_eq!(s, "12 34 5");
+        let s = declare_grouped_str!(["a", "b", "c", "d", "e", "f", "g"], 3, ',', " | ");
+        assert_eq!(s, "a,b,c | d,e,f | g");
+        let s = declare_grouped_str!(["a", "b"], 2, "-", " ");
+        assert_eq!(s, "a-b");
+        let s = declare_grouped_str!(["a", "b"], 1, "-", " ");
+        assert_eq!(s, "a b");
+    }
+
+    #[test]
+    fn replace_char() {
+        assert_eq!(replace_char_str!("a.b.c", '.', '/'), "a/b/c");
+        assert_eq!(replace_char_str!("a.b.c", ',', '/'), "a.b.c");
+        assert_eq!(replace_char_str!("ä b", ' ', '_'), "ä_b");
+        assert_eq!(replace_char_str!("", ' ', '_'), "");
+    }
+
+    #[test]
+    fn normalize_newlines() {
+        const MIXED: &str = "a\r\nb\rc\nd\r\n\r\n";
+        assert_eq!(normalize_newlines_str!(MIXED, Unix), "a\nb\nc\nd\n\n");
+        assert_eq!(
+            normalize_newlines_str!(MIXED, Windows),
+            "a\r\nb\r\nc\r\nd\r\n\r\n"
+        );
+        assert_eq!(normalize_newlines_str!("\n\r", Windows), "\r\n\r\n");
+        assert_eq!(normalize_newlines_str!("no newline", Unix), "no newline");
+    }
+
+    const fn upper_len(s: &str) -> usize {
+        s.len()
+    }
+
+    const fn write_upper(s: &str, out: &mut [u8]) -> usize {
+        let bytes = s.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            out[i] = bytes[i].to_ascii_uppercase();
+            i += 1;
+        }
+        i
+    }
+
+    #[test]
+    fn map_join() {
+        let s = declare_joined_map_str!(["get", "post"], '|', upper_len, write_upper);
+        assert_eq!(s, "GET|POST");
+        let s = declare_joined_map_str!([], '|', upper_len, write_upper);
+        assert_eq!(s, "");
+    }
+
+    const fn oxford_comma(_prev: &str, _next: &str, gap: usize, gaps: usize) -> &'static str {
+        if gaps == 1 {
+            " and "
+        } else if gap + 1 == gaps {
+            ", and "
+        } else {
+            ", "
+        }
+    }
+
+    const fn path_sep(prev: &str, _next: &str, _gap: usize, _gaps: usize) -> &'static str {
+        if prev.as_bytes()[prev.len() - 1] == b'/' {
+            ""
+        } else {
+            "/"
+        }
+    }
+
+    #[test]
+    fn dyn_sep() {
+        let s = declare_joined_dyn_sep_str!(["a", "b", "c", "d"], oxford_comma);
+        assert_eq!(s, "a, b, c, and d");
+        let s = declare_joined_dyn_sep_str!(["a", "b"], oxford_comma);
+        assert_eq!(s, "a and b");
+        let s = declare_joined_dyn_sep_str!(["a"], oxford_comma);
+        assert_eq!(s, "a");
+        let s = declare_joined_dyn_sep_str!(["usr/", "local", "bin"], path_sep);
+        assert_eq!(s, "usr/local/bin");
+    }
+
+    #[test]
+    fn concat_arrays() {
+        let a = concat_arrays!(["a", "b"], ["c"]);
+        assert_eq!(a, ["a", "b", "c"]);
+        let a = concat_arrays!(concat_arrays!(["a"], ["b"]), ["c", "d"]);
+        assert_eq!(a, ["a", "b", "c", "d"]);
+        let a = concat_arrays!(["a"], [], ARRAY_OF_STRINGS);
+        assert_eq!(a, ["a", "A", "B", "C"]);
+        let s = const_join!(concat_arrays!(ARRAY_OF_STRINGS, ["D"]), ",");
+        assert_eq!(s, "A,B,C,D");
+    }
+
+    #[test]
+    fn each_affixed() {
+        let s = declare_each_affixed_join_str!(["a", "b"], ",", "<", "");
+        assert_eq!(s, "<a,<b");
+        let s = declare_each_affixed_join_str!(["a", "b"], ",", "", ">");
+        assert_eq!(s, "a>,b>");
+        let s = declare_each_affixed_join_str!(["a", "b"], ",", "<", ">");
+        assert_eq!(s, "<a>,<b>");
+        let s = declare_each_affixed_join_str!([], ",", "<", ">");
+        assert_eq!(s, "");
+    }
+
+    #[test]
+    fn distinct_prefix() {
+        let s = declare_joined_distinct_prefix_str!(["app::a", "app::b"], "::", ", ");
+        assert_eq!(s, "app::a, b");
+        let s = declare_joined_distinct_prefix_str!(
+            ["app::ui::a", "app::ui::b", "app::uix"],
+            "::",
+            ", "
+        );
+        assert_eq!(s, "app::ui::a, ui::b, uix");
+        let s = declare_joined_distinct_prefix_str!(["a::x", "b::y"], "::", ", ");
+        assert_eq!(s, "a::x, b::y");
+        let s = declare_joined_distinct_prefix_str!(["app_a", "app_b"], "::", ", ");
+        assert_eq!(s, "app_a, app_b");
+        let s = declare_joined_distinct_prefix_str!(["a/b/c"], '/', ", ");
+        assert_eq!(s, "a/b/c");
+    }
+
+    #[test]
+    fn title_case() {
+        assert_eq!(title_case_str!("hello world foo"), "Hello World Foo");
+        assert_eq!(title_case_str!("hello   world"), "Hello   World");
+        assert_eq!(title_case_str!(" leading space"), " Leading Space");
+        assert_eq!(title_case_str!("1st place\téclair"), "1st Place\téclair");
+        assert_eq!(title_case_str!("mIxEd"), "MIxEd");
+    }
+
+    #[test]
+    fn size_policy() {
+        let exact: [u8; 5] = joined_array!(ARRAY_OF_STRINGS, ",", 5, SizePolicy::Exact);
+        assert_eq!(&exact, b"A,B,C");
+        let padded: [u8; 8] = joined_array!(ARRAY_OF_STRINGS, ",", 8, SizePolicy::Pad);
+        assert_eq!(&padded, b"A,B,C\0\0\0");
+        let truncated: [u8; 3] = joined_array!(ARRAY_OF_STRINGS, ",", 3, SizePolicy::Truncate);
+        assert_eq!(&truncated, b"A,B");
+        let long: [u8; 6] = joined_array!(ARRAY_OF_STRINGS, ",", 6, SizePolicy::Truncate);
+        assert_eq!(&long, b"A,B,C\0");
+
+        let s = const_join!(ARRAY_OF_STRINGS, ",", 5, SizePolicy::Exact);
+        assert_eq!(s, "A,B,C");
+        let s = const_join!(ARRAY_OF_STRINGS, ",", 4, SizePolicy::Truncate);
+        assert_eq!(s, "A,B,");
+        let s = const_join!(ARRAY_OF_STRINGS, ",", 9, SizePolicy::Truncate);
+        assert_eq!(s, "A,B,C");
+        // "ä" is two bytes, cutting in between leaves it out entirely
+        let s = const_join!(["a", "ä"], "-", 3, SizePolicy::Truncate);
+        assert_eq!(s, "a-");
+    }
+
+    #[test]
+    fn rot13() {
+        assert_eq!(rot13_str!("Hello, World!"), "Uryyb, Jbeyq!");
+        assert_eq!(rot13_str!("abc XYZ 123"), "nop KLM 123");
+        assert_eq!(rot13_str!("grüße"), "teüßr");
+        assert_eq!(rot13_str!(rot13_str!("Round Trip 42")), "Round Trip 42");
+    }
+
+    #[test]
+    fn cycle_sep() {
+        let s = declare_joined_cycle_sep_str!(["a", "b", "c", "d"], [",", ";"]);
+        assert_eq!(s, "a,b;c,d");
+        // three separators don't divide the five gaps
+        let s = declare_joined_cycle_sep_str!(["1", "2", "3", "4", "5", "6"], ["+", "-", "*"]);
+        assert_eq!(s, "1+2-3*4+5-6");
+        let s = declare_joined_cycle_sep_str!(ARRAY_OF_STRINGS, [", "]);
+        assert_eq!(s, "A, B, C");
+        let s = declare_joined_cycle_sep_str!(["only"], [",", ";"]);
+        assert_eq!(s, "only");
+    }
+
+    #[test]
+    fn strip_comments() {
+        assert_eq!(
+            strip_comments_str!("int x = 1; // the answer"),
+            "int x = 1; "
+        );
+        assert_eq!(
+            strip_comments_str!("// header\nfn f() {}\n"),
+            "\nfn f() {}\n"
+        );
+        assert_eq!(strip_comments_str!("a / b // c\nd"), "a / b \nd");
+        assert_eq!(strip_comments_str!("no comments"), "no comments");
+        // naive: string literals aren't special
+        assert_eq!(strip_comments_str!("\"http://x\""), "\"http:");
+    }
+
+    #[test]
+    fn trim_nul() {
+        let s = trim_nul_str!(joined_array!(ARRAY_OF_STRINGS, "-", 10, SizePolicy::Pad));
+        assert_eq!(s, "A-B-C");
+        let s = trim_nul_str!(*b"a\0b\0\0");
+        assert_eq!(s, "a\0b");
+        let s = trim_nul_str!([0u8; 4]);
+        assert_eq!(s, "");
+        let s = trim_nul_str!(*b"full");
+        assert_eq!(s, "full");
+    }
+
+    #[test]
+    fn joined_str_from_bytes() {
+        let s = declare_joined_str_from_bytes!([b"a", b"bc", b"def"], b",");
+        assert_eq!(s, "a,bc,def");
+        // the separator completes the char started by the first element
+        let s = declare_joined_str_from_bytes!([b"a\xc3", b"b"], b"\xa4");
+        assert_eq!(s, "aäb");
+        const PARTS: [&[u8]; 2] = [b"x", b"y"];
+        let s = declare_joined_str_from_bytes!(PARTS, b"");
+        assert_eq!(s, "xy");
+    }
+
+    #[test]
+    fn pluralize() {
+        assert_eq!(pluralize_str!("item", 1), "item");
+        assert_eq!(pluralize_str!("item", 2), "items");
+        assert_eq!(pluralize_str!("item", 0), "items");
+
+        assert_eq!(count_noun_str!(1, "item", "items"), "1 item");
+        assert_eq!(count_noun_str!(12, "item", "items"), "12 items");
+        assert_eq!(count_noun_str!(0, "mouse", "mice"), "0 mice");
+    }
+
+    #[test]
+    fn join_numbers() {
+        assert_eq!(join_numbers_str!([1, 22, 333], ","), "1,22,333");
+        assert_eq!(join_numbers_str!([0, 10, 100], ", "), "0, 10, 100");
+        assert_eq!(join_numbers_str!([usize::MAX], ""), "18446744073709551615");
+        assert_eq!(join_numbers_str!([], ","), "");
+
+        assert_eq!(join_signed_numbers_str!([-5, 0, 17], ","), "-5,0,17");
+        assert_eq!(
+            join_signed_numbers_str!([i64::MIN, i64::MAX], ' '),
+            "-9223372036854775808 9223372036854775807"
+        );
+    }
+
+    #[test]
+    fn fancy_join() {
+        let s = declare_fancy_join_str!(ARRAY_OF_STRINGS, JoinOptions::new());
+        assert_eq!(s, "ABC");
+
+        const ARGS: JoinOptions = JoinOptions {
+            sep: ", ",
+            prefix: "(",
+            suffix: ")",
+            ..JoinOptions::new()
+        };
+        let s = declare_fancy_join_str!(ARRAY_OF_STRINGS, ARGS);
+        assert_eq!(s, "(A, B, C)");
+        let s = declare_fancy_join_str!([], ARGS);
+        assert_eq!(s, "()");
+
+        const PATH: JoinOptions = JoinOptions {
+            sep: "/",
+            leading: true,
+            ..JoinOptions::new()
+        };
+        let s = declare_fancy_join_str!(["usr", "lib"], PATH);
+        assert_eq!(s, "/usr/lib");
+
+        const LINES: JoinOptions = JoinOptions {
+            sep: "\n",
+            prefix: "# ",
+            trailing: true,
+            ..JoinOptions::new()
+        };
+        let s = declare_fancy_join_str!(["a", "b"], LINES);
+        assert_eq!(s, "# a\nb\n");
+    }
+
+    #[test]
+    fn expand_tabs() {
+        assert_eq!(expand_tabs_str!("\tx", 4), "    x");
+        assert_eq!(expand_tabs_str!("ab\tc", 4), "ab  c");
+        assert_eq!(expand_tabs_str!("abcd\te", 4), "abcd    e");
+        assert_eq!(expand_tabs_str!("a\t\tb", 2), "a   b");
+        assert_eq!(expand_tabs_str!("abc\n\td", 4), "abc\n    d");
+        // multi-byte chars take up a single column
+        assert_eq!(expand_tabs_str!("ä\tb", 4), "ä   b");
+    }
+
+    #[test]
+    fn length_prefixed() {
+        assert_eq!(declare_length_prefixed_str!(["ab", "cde"]), "2ab3cde");
+        assert_eq!(declare_length_prefixed_str!([""]), "0");
+        assert_eq!(declare_length_prefixed_str!(["ä"]), "2ä");
+        assert_eq!(
+            declare_length_prefixed_str!(["0123456789ab", "x"]),
+            "120123456789ab1x"
+        );
+    }
+
+    #[test]
+    fn bracketed_join() {
+        assert_eq!(declare_bracketed_join_str!(["a"], ",", "(", ")"), "(a)");
+        assert_eq!(
+            declare_bracketed_join_str!(ARRAY_OF_STRINGS, ' ', "[", "]"),
+            "[A] [B] [C]"
+        );
+        assert_eq!(
+            declare_bracketed_join_str!(ARRAY_OF_STRINGS, ",", "", ""),
+            const_join!(ARRAY_OF_STRINGS, ",")
+        );
+    }
+
+    #[test]
+    fn dedent() {
+        assert_eq!(dedent_str!("    a\n    b"), "a\nb");
+        assert_eq!(dedent_str!("  a\n    b\n   c\n"), "a\n  b\n c\n");
+        assert_eq!(dedent_str!("    a\n\n    b"), "a\n\nb");
+        assert_eq!(dedent_str!("    a\n  \n      b"), "a\n\n  b");
+        assert_eq!(dedent_str!("a\n    b"), "a\n    b");
+        assert_eq!(dedent_str!("\ta\n\tb"), "a\nb");
+        assert_eq!(dedent_str!("   "), "   ");
+    }
+
+    #[test]
+    fn lines_maxwidth() {
+        let s = declare_lines_maxwidth!(["12345", "äöüäö"], 5);
+        assert_eq!(s, "12345\näöüäö");
+        let s = declare_lines_maxwidth!(["ab\ncd", "ef"], 2);
+        assert_eq!(s, "ab\ncd\nef");
+        assert_eq!(first_wide_line("ok\ntoo long\nok", 3), Some(1));
+        assert_eq!(first_wide_line("a\nbb\nccc", 3), None);
+    }
+
+    #[test]
+    fn reverse_words() {
+        assert_eq!(reverse_words_str!("hello world"), "world hello");
+        assert_eq!(
+            reverse_words_str!(" leading and trailing "),
+            "trailing and leading"
+        );
+        assert_eq!(reverse_words_str!("a  b\tc\nd"), "d c b a");
+        assert_eq!(reverse_words_str!("single"), "single");
+        assert_eq!(reverse_words_str!("   "), "");
+    }
+
+    #[test]
+    fn doc_block() {
+        assert_eq!(
+            declare_doc_block!(["Line one.", "Line two."]),
+            "/// Line one.\n/// Line two."
+        );
+        assert_eq!(
+            declare_doc_block!(["Summary.", "", "Details."]),
+            "/// Summary.\n///\n/// Details."
+        );
+        assert_eq!(declare_doc_block!([""]), "///");
+        assert_eq!(declare_doc_block!([]), "");
+    }
+
+    #[test]
+    fn common_suffix() {
+        assert_eq!(common_suffix_len(&["same", "same", "same"]), 4);
+        assert_eq!(common_suffix_len(&["alone"]), 5);
+        assert_eq!(common_suffix_len(&["ab", "cd"]), 0);
+        assert_eq!(common_suffix_len(&["a.rs", "rs"]), 2);
+        assert_eq!(common_suffix_len(&[]), 0);
+        // "ä" and "Ĥ" share their last byte
+        assert_eq!(common_suffix_len(&["ä", "Ĥ"]), 0);
+        assert_eq!(common_suffix_len(&["aä", "Ĥä"]), 2);
+
+        assert_eq!(common_suffix_str!(["foo.tar.gz", "bar.tar.gz"]), ".tar.gz");
+        assert_eq!(common_suffix_str!(["x", "y"]), "");
+        assert_eq!(common_suffix_str!([]), "");
+    }
+
+    #[test]
+    fn concat_elements() {
+        let s = const_join!([concat!("a", "b"), "c"], ",");
+        assert_eq!(s, "ab,c");
+        let s = const_join!([concat!("a", "b"), env!("CARGO_PKG_NAME")], '/');
+        assert_eq!(s, "ab/const-str-join");
+        let bytes: [u8; 4] = joined_array!([concat!("a", "b"), "c"], ",");
+        assert_eq!(&bytes, b"ab,c");
+        let s = declare_lines!([concat!("# ", "title"), "body"]);
+        assert_eq!(s, "# title\nbody");
+    }
+
+    #[test]
+    fn sanitize_filename() {
+        assert_eq!(
+            sanitize_filename_str!("<a>\\b|c?\"d\".txt"),
+            "_a__b_c__d_.txt"
+        );
+        assert_eq!(sanitize_filename_str!("tab\there\n"), "tab_here_");
+        assert_eq!(
+            sanitize_filename_str!("already-clean_ä.rs"),
+            "already-clean_ä.rs"
+        );
+    }
+
+    #[test]
+    fn indexed_join() {
+        let s = declare_indexed_join_str!(ARRAY_OF_STRINGS, ", ", "(", ")");
+        assert_eq!(s, "A(0), B(1), C(2)");
+        let s = declare_indexed_join_str!(["x"], ",", "", "");
+        assert_eq!(s, "x0");
+        // indices 9 and 10 differ in their number of digits
+        let s = declare_indexed_join_str!(
+            ["a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k"],
+            " ",
+            "[",
+            "]"
+        );
+        assert_eq!(s, "a[0] b[1] c[2] d[3] e[4] f[5] g[6] h[7] i[8] j[9] k[10]");
+    }
+
+    #[test]
+    fn sorted() {
+        assert!(is_sorted(&ARRAY_OF_STRINGS));
+        assert!(is_sorted(&["a", "a", "b"]));
+        assert!(is_sorted(&[]));
+        assert!(!is_sorted(&["a", "c", "b"]));
+        assert_eq!(first_unsorted_index(&["a", "c", "b"]), Some(2));
+        assert_eq!(first_unsorted_index(&["ab", "a"]), Some(1));
+
+        let s = declare_joined_assert_sorted_str!(["a", "b", "b"], "<=");
+        assert_eq!(s, "a<=b<=b");
+    }
+
+    #[test]
+    fn rjust_join() {
+        let s = declare_rjust_join_str!(["7", "42", "100", "5"], ",", ' ');
+        assert_eq!(s, "  7, 42,100,  5");
+        let s = declare_rjust_join_str!(["1", "22"], ' ', '0');
+        assert_eq!(s, "01 22");
+        // widths are counted in chars, the fill may be multi-byte as well
+        let s = declare_rjust_join_str!(["äö", "a", "abc"], "|", '·');
+        assert_eq!(s, "·äö|··a|abc");
+    }
+
+    #[test]
+    fn split_once_pairs() {
+        assert_eq!(split_once("KEY=value", "="), Some(("KEY", "value")));
+        assert_eq!(split_once("=value", "="), Some(("", "value")));
+        assert_eq!(split_once("key=", "="), Some(("key", "")));
+        assert_eq!(split_once("a::b::c", "::"), Some(("a", "b::c")));
+        assert_eq!(split_once("ä→ö", "→"), Some(("ä", "ö")));
+        assert_eq!(split_once("abc", ""), Some(("", "abc")));
+        assert_eq!(split_once("KEY", "="), None);
+
+        let (key, value) = split_once_str!("PATH=/usr/bin", '=');
+        assert_eq!(key, "PATH");
+        assert_eq!(value, "/usr/bin");
+    }
+
+    #[test]
+    fn permuted_join() {
+        let s = declare_permuted_join_str!(ARRAY_OF_STRINGS, ",", [2, 1, 0]);
+        assert_eq!(s, "C,B,A");
+        let s = declare_permuted_join_str!(ARRAY_OF_STRINGS, "", [0, 1, 2]);
+        assert_eq!(s, "ABC");
+        let s = declare_permuted_join_str!(["first", "second"], ' ', [1, 0]);
+        assert_eq!(s, "second first");
+
+        assert_eq!(first_invalid_permutation_index(&[1, 0, 2], 3), None);
+        assert_eq!(first_invalid_permutation_index(&[1, 3, 2], 3), Some(1));
+        assert_eq!(first_invalid_permutation_index(&[1, 2, 1], 3), Some(2));
+    }
+
+    #[test]
+    fn joined_str_with_len() {
+        declare_joined_str_with_len!(JOINED, JOINED_LEN, ARRAY_OF_STRINGS, ", ");
+        let s = JOINED;
+        assert_eq!(s, "A, B, C");
+        assert_eq!(JOINED_LEN, s.len());
+        assert_eq!(JOINED_LEN, 7);
+    }
+
+    #[test]
+    fn joined_static() {
+        declare_joined_static!(SHARED, SHARED_BYTES, ARRAY_OF_STRINGS, "+");
+        assert_eq!(SHARED, "A+B+C");
+        assert_eq!(&SHARED_BYTES, b"A+B+C");
+        assert!(core::ptr::eq(SHARED.as_bytes(), &SHARED_BYTES));
+    }
+
+    #[test]
+    fn aligned_kv() {
+        let s = declare_aligned_kv_str!([("a", "1"), ("bbb", "2"), ("cc", "3")], ": ", '\n');
+        assert_eq!(s, "a  : 1\nbbb: 2\ncc : 3");
+        let s = declare_aligned_kv_str!([("größe", "x"), ("name", "y")], "=", ", ");
+        assert_eq!(s, "größe=x, name =y");
+        let s = declare_aligned_kv_str!([("only", "one")], " = ", "\n");
+        assert_eq!(s, "only = one");
+    }
+
+    #[test]
+    fn pascal_case() {
+        assert_eq!(pascal_case_str!(["my", "http", "server"]), "MyHttpServer");
+        assert_eq!(pascal_case_str!(["v", "2nd", "try"]), "V2ndTry");
+        assert_eq!(pascal_case_str!(["HTTP", "", "client"]), "HTTPClient");
+        assert_eq!(pascal_case_str!(["über", "x"]), "überX");
+    }
+
+    #[test]
+    fn inline_str() {
+        let s = declare_inline_str!(ARRAY_OF_STRINGS, ", ", 32);
+        assert_eq!(s.as_str(), "A, B, C");
+        assert_eq!(s.as_bytes(), b"A, B, C");
+        assert_eq!(s.len(), 7);
+        assert!(!s.is_empty());
+
+        let exact = declare_inline_str!(ARRAY_OF_STRINGS, "", 3);
+        assert_eq!(exact.as_str(), "ABC");
+
+        const EMPTY: InlineStr<4> = InlineStr::new("");
+        assert!(EMPTY.is_empty());
+        assert_eq!(InlineStr::<4>::new("äb"), InlineStr::<4>::new("äb"));
+    }
+
+    #[test]
+    fn joined_with_hash() {
+        let s = declare_joined_with_hash_str!(["a"], ",");
+        assert_eq!(s, "a#e40c292c");
+        let abc = declare_joined_with_hash_str!(ARRAY_OF_STRINGS, ",", "--");
+        let abd = declare_joined_with_hash_str!(["A", "B", "D"], ",", "--");
+        assert_eq!(abc.len(), "A,B,C--".len() + 8);
+        assert!(abc.starts_with("A,B,C--"));
+        assert_ne!(abc.split_at(7).1, abd.split_at(7).1);
+    }
+
+    #[test]
+    fn unescape() {
+        assert_eq!(unescape_str!(r"a\nb\tc\rd"), "a\nb\tc\rd");
+        assert_eq!(unescape_str!(r#"\\ \" \' \0"#), "\\ \" ' \0");
+        assert_eq!(unescape_str!(r"\x41\x7f\x0a"), "A\x7f\n");
+        assert_eq!(unescape_str!("plain ä"), "plain ä");
+
+        assert_eq!(first_invalid_escape_index(r"ok \q"), Some(3));
+        assert_eq!(first_invalid_escape_index(r"trailing \"), Some(9));
+        assert_eq!(first_invalid_escape_index(r"\x8f"), Some(0));
+        assert_eq!(first_invalid_escape_index(r"\x4"), Some(0));
+        assert_eq!(first_invalid_escape_index(r"\\q"), None);
+    }
+
+    mod generated {
+        include!("fixtures/generated_names.rs");
+    }
+
+    #[test]
+    fn included_arrays() {
+        let s = const_join!(generated::GENERATED_NAMES, ",");
+        assert_eq!(s, "alpha,beta,gamma");
+        let s = declare_lines!(generated::GENERATED_NAMES);
+        assert_eq!(s, "alpha\nbeta\ngamma");
+        // a file containing just the array expression
+        let s = const_join!(include!("fixtures/generated_names_expr.rs"), ' ');
+        assert_eq!(s, "alpha beta gamma");
+        let bytes: [u8; 16] = joined_array!(include!("fixtures/generated_names_expr.rs"), ",");
+        assert_eq!(&bytes, b"alpha,beta,gamma");
+    }
+
+    #[test]
+    fn assert_lines() {
+        assert_eq!(line_count(""), 0);
+        assert_eq!(line_count("single"), 1);
+        assert_eq!(line_count("a\n"), 2);
+        assert_eq!(line_count("\n\n"), 3);
+
+        let s = declare_joined_str_assert_lines!(ARRAY_OF_STRINGS, "\n", 3);
+        assert_eq!(s, "A\nB\nC");
+        let s = declare_joined_str_assert_lines!(ARRAY_OF_STRINGS, ",", 1);
+        assert_eq!(s, "A,B,C");
+        let s = declare_joined_str_assert_lines!(["a", ""], "\n", 2);
+        assert_eq!(s, "a\n");
+    }
+
+    #[test]
+    fn offset_annotated() {
+        let s = declare_offset_annotated_str!(["ab", "cde"], "\n");
+        assert_eq!(s, "0:ab\n2:cde");
+        let s = declare_offset_annotated_str!(["0123456789", "", "ab", "c"], ' ');
+        assert_eq!(s, "0:0123456789 10: 10:ab 12:c");
+    }
+
+    #[test]
+    fn rust_array_literal() {
+        assert_eq!(declare_rust_array_literal_str!([]), "[]");
+        assert_eq!(declare_rust_array_literal_str!(["a"]), r#"["a"]"#);
+        assert_eq!(
+            declare_rust_array_literal_str!(["a", "b", "c"]),
+            r#"["a", "b", "c"]"#
+        );
+        assert_eq!(
+            declare_rust_array_literal_str!(["\"quoted\"", "back\\slash", "tab\tnl\n\x01", "ä"]),
+            r#"["\"quoted\"", "back\\slash", "tab\tnl\n\x01", "ä"]"#
+        );
+    }
+
+    #[test]
+    fn smart_join() {
+        let s = declare_joined_smart_str!(["a\n", "b\n", "c"], "\n");
+        assert_eq!(s, "a\nb\nc");
+        let s = declare_joined_smart_str!(ARRAY_OF_STRINGS, "\n");
+        assert_eq!(s, "A\nB\nC");
+        let s = declare_joined_smart_str!(["usr/", "local", "bin/"], '/');
+        assert_eq!(s, "usr/local/bin/");
+        let s = declare_joined_smart_str!(["a", "b"], "");
+        assert_eq!(s, "ab");
+    }
+
+    #[test]
+    fn bounds_checked_get() {
+        assert_eq!(get_str(&ARRAY_OF_STRINGS, 2), "C");
+        assert_eq!(get_byte(b"xyz", 1), b'y');
+        assert_eq!(get_elem(&[("a", 1), ("b", 2)], 1), &("b", 2));
+    }
+
+    #[test]
+    #[should_panic(expected = "index 3 out of bounds for length 3")]
+    fn get_str_out_of_bounds() {
+        get_str(&ARRAY_OF_STRINGS, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "index 5 out of bounds for length 2")]
+    fn get_byte_out_of_bounds() {
+        get_byte(b"ab", 5);
+    }
+
+    #[test]
+    fn zeropad_join() {
+        assert_eq!(declare_joined_zeropad_str!([0, 7], "-", 3), "000-007");
+        assert_eq!(declare_joined_zeropad_str!([123, 45], ",", 3), "123,045");
+        assert_eq!(declare_joined_zeropad_str!([12345, 6], ' ', 3), "12345 006");
+        assert_eq!(declare_joined_zeropad_str!([5], ",", 0), "5");
+    }
+
+    #[test]
+    fn collisions() {
+        assert_eq!(first_collision(&ARRAY_OF_STRINGS, ","), None);
+        assert_eq!(first_collision(&["a", "b::c", "d::"], "::"), Some(1));
+        assert_eq!(first_collision(&["a", "b"], ""), None);
+        assert_eq!(first_collision(&[], ","), None);
+
+        let s = declare_joined_checked_str!(ARRAY_OF_STRINGS, "::");
+        assert_eq!(s, "A::B::C");
+    }
+
+    #[test]
+    fn tight_join() {
+        assert_eq!(declare_joined_tight_str!(["a", "", "b"], "-"), "a-b");
+        assert_eq!(declare_joined_tight_str!(["", "a", "b"], "-"), "a-b");
+        assert_eq!(declare_joined_tight_str!(["a", "b", ""], "-"), "a-b");
+        assert_eq!(declare_joined_tight_str!(["", "", ""], "-"), "");
+        assert_eq!(declare_joined_tight_str!(ARRAY_OF_STRINGS, ", "), "A, B, C");
+    }
+
+    #[test]
+    fn c_define() {
+        assert_eq!(declare_c_define_str!("DEBUG", "1"), "#define DEBUG 1");
+        assert_eq!(
+            declare_c_define_str!("VERSION", concat!("\"", env!("CARGO_PKG_VERSION"), "\"")),
+            concat!("#define VERSION \"", env!("CARGO_PKG_VERSION"), "\"")
+        );
+        assert_eq!(
+            declare_c_define_str!("POINT", ["1", "2"], ','),
+            "#define POINT 1,2"
+        );
+        assert_eq!(
+            declare_c_define_str!("NAMES", ARRAY_OF_STRINGS, " | "),
+            "#define NAMES A | B | C"
+        );
+    }
+
+    #[test]
+    fn widest() {
+        assert_eq!(widest_index(&["ab", "cd", "e"]), Some(0));
+        assert_eq!(widest_index(&["a", "bc", "de"]), Some(1));
+        // three bytes but only two chars
+        assert_eq!(widest_index(&["äb", "abc"]), Some(1));
+        assert_eq!(widest_index(&[""]), Some(0));
+        assert_eq!(widest_index(&[]), None);
+    }
+
+    #[test]
+    fn url_path() {
+        assert_eq!(
+            declare_url_path_str!(["api", "v1", "users"]),
+            "api/v1/users"
+        );
+        assert_eq!(declare_url_path_str!(["hello world"]), "hello%20world");
+        assert_eq!(declare_url_path_str!(["a/b", "c?d#e"]), "a%2Fb/c%3Fd%23e");
+        assert_eq!(declare_url_path_str!(["ä", "x-y_z.~"]), "%C3%A4/x-y_z.~");
+        assert_eq!(declare_url_path_str!(["", "root"]), "/root");
+    }
+
+    #[test]
+    fn etag() {
+        let strong = declare_etag_str!(ARRAY_OF_STRINGS, ",");
+        let weak = declare_etag_str!(ARRAY_OF_STRINGS, ",", true);
+        let other = declare_etag_str!(["A", "B", "D"], ",");
+        assert_eq!(strong.len(), 10);
+        assert!(strong.starts_with('"') && strong.ends_with('"'));
+        assert!(!strong.starts_with("W/"));
+        assert_eq!(weak.split_at(2), ("W/", strong));
+        assert_ne!(strong, other);
+        assert_eq!(declare_etag_str!(["a"], ","), "\"e40c292c\"");
+    }
+
+    #[test]
+    fn copy_str() {
+        let mut buffer = [b'x'; 4];
+        let offset = copy_str_into("ab", &mut buffer, 0);
+        assert_eq!(offset, 2);
+        assert_eq!(copy_str_into("cd", &mut buffer, offset), 4);
+        assert_eq!(&buffer, b"abcd");
+        assert_eq!(copy_str_into("", &mut buffer, 4), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "source string doesn't fit into the destination buffer")]
+    fn copy_str_overflow() {
+        let mut buffer = [0u8; 4];
+        copy_str_into("abc", &mut buffer, 2);
+    }
+
+    #[test]
+    fn two_level_join() {
+        let s = declare_two_level_join_str!([["a", "b"], ["c"], ["d", "e", "f"]], ",", ";");
+        assert_eq!(s, "a,b;c;d,e,f");
+        let s = declare_two_level_join_str!([ARRAY_OF_STRINGS], ",", ";");
+        assert_eq!(s, "A,B,C");
+        let s = declare_two_level_join_str!([["a"], [], ["b"]], ",", ";");
+        assert_eq!(s, "a;;b");
+        let s = declare_two_level_join_str!([], ",", ";");
+        assert_eq!(s, "");
+    }
+
+    #[test]
+    fn abbreviate() {
+        assert_eq!(abbreviate_str!("portable network graphics"), "PNG");
+        assert_eq!(abbreviate_str!("as  soon   as possible"), "ASAP");
+        assert_eq!(abbreviate_str!(" leading space"), "LS");
+        assert_eq!(abbreviate_str!("web 2 point zero"), "WPZ");
+        assert_eq!(abbreviate_str!("über alles"), "A");
+        assert_eq!(abbreviate_str!(""), "");
+    }
+
+    #[test]
+    fn group_digits() {
+        assert_eq!(group_digits_str!("123", ","), "123");
+        assert_eq!(group_digits_str!("1234", ","), "1,234");
+        assert_eq!(group_digits_str!("1234567", ","), "1,234,567");
+        assert_eq!(group_digits_str!("", ","), "");
+        assert_eq!(group_digits_str!(0, ","), "0");
+        assert_eq!(group_digits_str!(999, ","), "999");
+        assert_eq!(group_digits_str!(1000, "."), "1.000");
+        assert_eq!(group_digits_str!(1234567, "'"), "1'234'567");
+        const BYTES: usize = 65536;
+        assert_eq!(group_digits_str!(BYTES, ' '), "65 536");
+    }
+
+    #[test]
+    fn prefix_filter() {
+        let s = declare_joined_with_prefix_filter_str!(["a", "b"], ",", "--");
+        assert_eq!(s, "");
+        let s = declare_joined_with_prefix_filter_str!(["--a", "--b"], ",", "--");
+        assert_eq!(s, "--a,--b");
+        let s = declare_joined_with_prefix_filter_str!(["x", "-v", "--verbose", "-q"], ' ', "-");
+        assert_eq!(s, "-v --verbose -q");
+        let s = declare_joined_with_prefix_filter_str!(ARRAY_OF_STRINGS, ",", "");
+        assert_eq!(s, "A,B,C");
+    }
+
+    #[test]
+    fn joined_chars() {
+        assert_eq!(joined_char_count(&ARRAY_OF_STRINGS, ", "), 7);
+        assert_eq!(joined_char_count(&["日本", "語"], ""), 3);
+        assert_eq!(joined_char_count(&["a", "b", "c"], "→"), 5);
+        assert_eq!(joined_char_count!(["äb", "c"], '→'), 4);
+        assert_eq!(
+            joined_char_count!(["äb", "c"], "→"),
+            const_join!(["äb", "c"], "→").chars().count()
+        );
+    }
+
+    #[test]
+    fn rust_raw_str_literal() {
+        let s = declare_rust_raw_str_literal!(ARRAY_OF_STRINGS, ",");
+        assert_eq!(s, r#"r"A,B,C""#);
+        let s = declare_rust_raw_str_literal!(["a\"", "b"], "");
+        assert_eq!(s, r##"r#"a"b"#"##);
+        // `"#` in the content needs two hashes
+        let s = declare_rust_raw_str_literal!(["x = \"#", "y"], "\n");
+        assert_eq!(s, "r##\"x = \"#\ny\"##");
+        assert_eq!(raw_str_hashes("\"# \"###"), 4);
+    }
+
+    #[test]
+    fn swap_case() {
+        assert_eq!(swap_case_str!("Hello World"), "hELLO wORLD");
+        assert_eq!(swap_case_str!("123 -_ äÖ"), "123 -_ äÖ");
+        assert_eq!(swap_case_str!(swap_case_str!("MiXeD 42")), "MiXeD 42");
+    }
+
+    #[test]
+    fn alt_sep() {
+        let s = declare_joined_alt_sep_str!(["a", "b", "c", "d"], "-", " ");
+        assert_eq!(s, "a-b c-d");
+        let s = declare_joined_alt_sep_str!(["a", "b", "c", "d", "e"], '=', ", ");
+        assert_eq!(s, "a=b, c=d, e");
+        let s = declare_joined_alt_sep_str!(["a", "b"], "-", " ");
+        assert_eq!(s, "a-b");
+        let s = declare_joined_alt_sep_str!(["a"], "-", " ");
+        assert_eq!(s, "a");
+    }
+
+    #[test]
+    fn fixed_record() {
+        let s = declare_fixed_record_str!([("name", 10), ("code", 4)], ' ');
+        assert_eq!(s, "name      code");
+        let s = declare_fixed_record_str!([("42", 5), ("toolong", 3)], '0');
+        assert_eq!(s, "42000too");
+        // "ä" doesn't fit into the last byte and is replaced by fill
+        let s = declare_fixed_record_str!([("aä", 2), ("", 1)], '.');
+        assert_eq!(s, "a..");
+    }
+
+    #[test]
+    fn joined_with_more() {
+        let s = declare_joined_with_more_str!(ARRAY_OF_STRINGS, ", ", 3, " and {n} more");
+        assert_eq!(s, "A, B, C");
+        let s = declare_joined_with_more_str!(ARRAY_OF_STRINGS, ", ", 5, " and {n} more");
+        assert_eq!(s, "A, B, C");
+        let s = declare_joined_with_more_str!(ARRAY_OF_STRINGS, ", ", 1, " and {n} more");
+        assert_eq!(s, "A and 2 more");
+        let s = declare_joined_with_more_str!(
+            ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11"],
+            ",",
+            1,
+            "… ({n}/{n})"
+        );
+        assert_eq!(s, "0… (11/11)");
+        let s = declare_joined_with_more_str!(ARRAY_OF_STRINGS, ",", 0, "{n} items");
+        assert_eq!(s, "3 items");
     }
 }